@@ -41,9 +41,9 @@ mod apple_leak_bench {
                         }
                     }
 
-                    let result = perform_ocr_apple(&image, &[]);
+                    let result = perform_ocr_apple(&image, &[]).unwrap();
                     assert!(
-                        result.0.contains("receiver_count"),
+                        result.text().contains("receiver_count"),
                         "OCR failed: {:?}",
                         result
                     );
@@ -3,11 +3,14 @@ use std::path::PathBuf;
 use std::time::Duration;
 use strsim::jaro_winkler;
 
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+use uni_ocr::Preprocessor;
+
 #[cfg(target_os = "macos")]
 use uni_ocr::perform_ocr_apple;
 
 #[cfg(target_os = "linux")]
-use uni_ocr::perform_ocr_tesseract;
+use uni_ocr::{perform_ocr_tesseract, OcrOptions};
 
 #[cfg(target_os = "windows")]
 use uni_ocr::perform_ocr_windows;
@@ -82,8 +85,8 @@ fn bench_apple_vision_ocr(c: &mut Criterion) {
 
     group.bench_function(BenchmarkId::new("Performance", ""), |b| {
         b.iter(|| {
-            let result = perform_ocr_apple(black_box(&image), &[]);
-            assert!(!result.0.is_empty(), "OCR failed");
+            let result = perform_ocr_apple(black_box(&image), &[]).unwrap();
+            assert!(!result.is_empty(), "OCR failed");
         })
     });
 
@@ -104,10 +107,42 @@ fn bench_apple_vision_ocr_with_accuracy(c: &mut Criterion) {
 
             for _ in 0..iters {
                 let start = std::time::Instant::now();
-                let result = perform_ocr_apple(black_box(&image), &[]);
+                let result = perform_ocr_apple(black_box(&image), &[]).unwrap();
                 total_duration += start.elapsed();
 
-                let accuracy = calculate_accuracy(&result.0, EXPECTED_KEYWORDS);
+                let accuracy = calculate_accuracy(&result.text(), EXPECTED_KEYWORDS);
+                total_accuracy += accuracy;
+            }
+
+            println!("Average Accuracy: {:.2}", total_accuracy / iters as f32);
+            total_duration
+        })
+    });
+
+    group.finish();
+}
+
+// The test image is a dark-themed editor; compare against inverting it locally first
+#[cfg(target_os = "macos")]
+fn bench_apple_vision_ocr_dark_regions(c: &mut Criterion) {
+    let image = load_test_image();
+    let preprocessor = Preprocessor::default().invert_dark_regions(true);
+    let mut group = c.benchmark_group("Apple Vision OCR with dark regions inverted");
+    group.sample_size(10);
+    group.measurement_time(Duration::from_secs(10));
+
+    group.bench_function(BenchmarkId::new("Performance and Accuracy", ""), |b| {
+        b.iter_custom(|iters| {
+            let mut total_duration = Duration::new(0, 0);
+            let mut total_accuracy = 0.0;
+
+            for _ in 0..iters {
+                let start = std::time::Instant::now();
+                let prepared = preprocessor.apply(black_box(&image));
+                let result = perform_ocr_apple(&prepared, &[]).unwrap();
+                total_duration += start.elapsed();
+
+                let accuracy = calculate_accuracy(&result.text(), EXPECTED_KEYWORDS);
                 total_accuracy += accuracy;
             }
 
@@ -134,10 +169,41 @@ fn bench_tesseract_ocr(c: &mut Criterion) {
 
             for _ in 0..iters {
                 let start = std::time::Instant::now();
-                let (result, _, _) = perform_ocr_tesseract(black_box(&image), vec![]);
+                let result = perform_ocr_tesseract(black_box(&image), &OcrOptions::default()).unwrap();
+                total_duration += start.elapsed();
+
+                let accuracy = calculate_accuracy(&result.text(), EXPECTED_KEYWORDS);
+                total_accuracy += accuracy;
+            }
+
+            println!("Average Accuracy: {:.2}", total_accuracy / iters as f32);
+            total_duration
+        })
+    });
+
+    group.finish();
+}
+
+#[cfg(target_os = "linux")]
+fn bench_tesseract_ocr_dark_regions(c: &mut Criterion) {
+    let image = load_test_image();
+    let preprocessor = Preprocessor::default().invert_dark_regions(true);
+    let mut group = c.benchmark_group("Tesseract OCR with dark regions inverted");
+    group.sample_size(10);
+    group.measurement_time(Duration::from_secs(10));
+
+    group.bench_function(BenchmarkId::new("Performance and Accuracy", ""), |b| {
+        b.iter_custom(|iters| {
+            let mut total_duration = Duration::new(0, 0);
+            let mut total_accuracy = 0.0;
+
+            for _ in 0..iters {
+                let start = std::time::Instant::now();
+                let prepared = preprocessor.apply(black_box(&image));
+                let result = perform_ocr_tesseract(&prepared, &OcrOptions::default()).unwrap();
                 total_duration += start.elapsed();
 
-                let accuracy = calculate_accuracy(&result, EXPECTED_KEYWORDS);
+                let accuracy = calculate_accuracy(&result.text(), EXPECTED_KEYWORDS);
                 total_accuracy += accuracy;
             }
 
@@ -169,10 +235,10 @@ fn bench_windows_ocr(c: &mut Criterion) {
 
                     for _ in 0..iters {
                         let start = std::time::Instant::now();
-                        let (result, _, _) = perform_ocr_windows(black_box(&image)).await.unwrap();
+                        let result = perform_ocr_windows(black_box(&image)).await.unwrap();
                         total_duration += start.elapsed();
 
-                        let accuracy = calculate_accuracy(&result, EXPECTED_KEYWORDS);
+                        let accuracy = calculate_accuracy(&result.text(), EXPECTED_KEYWORDS);
                         total_accuracy += accuracy;
                     }
 
@@ -190,11 +256,16 @@ fn bench_windows_ocr(c: &mut Criterion) {
 criterion_group!(
     benches,
     bench_apple_vision_ocr,
-    bench_apple_vision_ocr_with_accuracy
+    bench_apple_vision_ocr_with_accuracy,
+    bench_apple_vision_ocr_dark_regions
 );
 
 #[cfg(target_os = "linux")]
-criterion_group!(benches, bench_tesseract_ocr);
+criterion_group!(
+    benches,
+    bench_tesseract_ocr,
+    bench_tesseract_ocr_dark_regions
+);
 
 #[cfg(target_os = "windows")]
 criterion_group!(benches, bench_windows_ocr);
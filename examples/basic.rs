@@ -7,10 +7,10 @@ async fn main() -> Result<()> {
     let engine = OcrEngine::new(OcrProvider::Auto)?;
 
     // Perform OCR on a single image
-    let (text, json, confidence) = engine.recognize_file("examples/sample.png").await?;
-    println!("Text: {}", text);
-    println!("Confidence: {:.2}%", confidence.unwrap_or(0.0));
-    println!("JSON details: {}", json);
+    let result = engine.recognize_file("examples/sample.png").await?;
+    println!("Text: {}", result.text());
+    println!("Confidence: {:.2}", result.confidence().unwrap_or(0.0));
+    println!("JSON details: {}", serde_json::to_string_pretty(&result)?);
 
     Ok(())
 }
@@ -11,12 +11,17 @@ async fn main() -> Result<()> {
         "examples/sample3.png",
     ];
 
-    let results = engine.recognize_batch(images).await?;
+    let results = engine.recognize_batch(images).await;
 
-    for (i, (text, _, confidence)) in results.iter().enumerate() {
+    for (i, result) in results.iter().enumerate() {
         println!("Image {}: ", i + 1);
-        println!("Text: {}", text);
-        println!("Confidence: {:.2}%", confidence.unwrap_or(0.0));
+        match result {
+            Ok(result) => {
+                println!("Text: {}", result.text());
+                println!("Confidence: {:.2}", result.confidence().unwrap_or(0.0));
+            }
+            Err(err) => println!("Failed: {}", err),
+        }
         println!("---");
     }
 
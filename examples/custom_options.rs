@@ -12,9 +12,9 @@ async fn main() -> Result<()> {
 
     let engine = OcrEngine::new(OcrProvider::Auto)?.with_options(options);
     
-    let (text, _, confidence) = engine.recognize_file("examples/multilingual.png").await?;
-    println!("Multilingual text: {}", text);
-    println!("Confidence: {:.2}%", confidence.unwrap_or(0.0));
+    let result = engine.recognize_file("examples/multilingual.png").await?;
+    println!("Multilingual text: {}", result.text());
+    println!("Confidence: {:.2}", result.confidence().unwrap_or(0.0));
 
     Ok(())
 } 
\ No newline at end of file
@@ -13,9 +13,9 @@ async fn main() -> Result<()> {
     #[cfg(not(any(target_os = "macos", target_os = "windows")))]
     let engine = OcrEngine::new(OcrProvider::Tesseract)?;
 
-    let (text, _, confidence) = engine.recognize_file("examples/sample.png").await?;
-    println!("Text: {}", text);
-    println!("Confidence: {:.2}%", confidence.unwrap_or(0.0));
+    let result = engine.recognize_file("examples/sample.png").await?;
+    println!("Text: {}", result.text());
+    println!("Confidence: {:.2}", result.confidence().unwrap_or(0.0));
 
     Ok(())
 }
@@ -1,12 +1,14 @@
 use anyhow::Result;
 use image::DynamicImage;
-use uni_ocr::{OcrEngine, OcrProvider};
+use uni_ocr::{OcrEngine, OcrOptions, OcrProvider, Preprocessor};
 use xcap::Monitor;
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Initialize OCR engine
-    let engine = OcrEngine::new(OcrProvider::Auto)?;
+    // Initialize OCR engine; 4K/5K captures are split into tiles recognized in parallel,
+    // and tiny UI labels are upscaled
+    let engine = OcrEngine::new(OcrProvider::Auto)?
+        .with_options(OcrOptions::default().preprocess(Preprocessor::screenshot()));
 
     // Get all monitors
     let monitors = Monitor::all()?;
@@ -21,11 +23,11 @@ async fn main() -> Result<()> {
         let dynamic_image = DynamicImage::ImageRgba8(image);
 
         // Perform OCR
-        let (text, _, confidence) = engine.recognize_image(&dynamic_image).await?;
+        let result = engine.recognize_image(&dynamic_image).await?;
 
         println!("monitor {}: ", i);
-        println!("text: {}", text);
-        println!("confidence: {:.2}%", confidence.unwrap_or(0.0));
+        println!("text: {}", result.text());
+        println!("confidence: {:.2}", result.confidence().unwrap_or(0.0));
         println!("---");
     }
 
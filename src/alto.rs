@@ -0,0 +1,199 @@
+use std::fmt::Write;
+
+use crate::result::{BoundingBox, OcrResult};
+use crate::xml::escape;
+
+/// Unit of the positions and sizes in an ALTO document.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MeasurementUnit {
+    #[default]
+    Pixel,
+    /// Tenths of a millimetre.
+    Mm10,
+    /// 1/1200 of an inch.
+    Inch1200,
+}
+
+impl MeasurementUnit {
+    fn name(&self) -> &'static str {
+        match self {
+            MeasurementUnit::Pixel => "pixel",
+            MeasurementUnit::Mm10 => "mm10",
+            MeasurementUnit::Inch1200 => "inch1200",
+        }
+    }
+}
+
+/// Settings for [`OcrResult::to_alto_with`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AltoOptions {
+    pub measurement_unit: MeasurementUnit,
+    /// Resolution of the recognized image, used to convert pixels to physical units.
+    pub dpi: f64,
+}
+
+impl Default for AltoOptions {
+    fn default() -> Self {
+        Self {
+            measurement_unit: MeasurementUnit::Pixel,
+            dpi: 300.0,
+        }
+    }
+}
+
+impl AltoOptions {
+    pub fn measurement_unit(mut self, unit: MeasurementUnit) -> Self {
+        self.measurement_unit = unit;
+        self
+    }
+
+    pub fn dpi(mut self, dpi: f64) -> Self {
+        self.dpi = dpi;
+        self
+    }
+
+    /// Converts pixels to the measurement unit, rounded.
+    fn convert(&self, pixels: f64) -> i64 {
+        let value = match self.measurement_unit {
+            MeasurementUnit::Pixel => pixels,
+            MeasurementUnit::Mm10 => pixels / self.dpi * 254.0,
+            MeasurementUnit::Inch1200 => pixels / self.dpi * 1200.0,
+        };
+        value.round() as i64
+    }
+
+    /// `HPOS`, `VPOS`, `WIDTH` and `HEIGHT` attributes, or nothing for an empty box.
+    fn position(&self, b: &BoundingBox) -> String {
+        if b.is_empty() {
+            return String::new();
+        }
+        format!(
+            r#" HPOS="{}" VPOS="{}" WIDTH="{}" HEIGHT="{}""#,
+            self.convert(b.x),
+            self.convert(b.y),
+            self.convert(b.width),
+            self.convert(b.height)
+        )
+    }
+}
+
+impl OcrResult {
+    /// Serializes the result as an ALTO v4 document, in pixels.
+    pub fn to_alto(&self) -> String {
+        self.to_alto_with(&AltoOptions::default())
+    }
+
+    /// Serializes the result as an ALTO v4 document.
+    ///
+    /// Each paragraph becomes a `TextBlock` and each word a `String`, with a `WC` confidence
+    /// of 0–1 when the provider reports one. Positions are left out for providers that report
+    /// no boxes.
+    pub fn to_alto_with(&self, options: &AltoOptions) -> String {
+        let mut alto = String::new();
+        // Writing to a String cannot fail
+        let _ = self.write_alto(options, &mut alto);
+        alto
+    }
+
+    fn write_alto(&self, options: &AltoOptions, out: &mut String) -> std::fmt::Result {
+        writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(
+            out,
+            r#"<alto xmlns="http://www.loc.gov/standards/alto/ns-v4#" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:schemaLocation="http://www.loc.gov/standards/alto/ns-v4# http://www.loc.gov/standards/alto/v4/alto-4-2.xsd">"#
+        )?;
+        writeln!(out, "  <Description>")?;
+        writeln!(
+            out,
+            "    <MeasurementUnit>{}</MeasurementUnit>",
+            options.measurement_unit.name()
+        )?;
+        writeln!(out, r#"    <Processing ID="OCR_0">"#)?;
+        if let Some(provider) = &self.provider {
+            writeln!(
+                out,
+                "      <processingStepSettings>provider: {}</processingStepSettings>",
+                escape(provider)
+            )?;
+        }
+        writeln!(out, "      <processingSoftware>")?;
+        writeln!(out, "        <softwareName>uni-ocr</softwareName>")?;
+        writeln!(
+            out,
+            "        <softwareVersion>{}</softwareVersion>",
+            env!("CARGO_PKG_VERSION")
+        )?;
+        writeln!(out, "      </processingSoftware>")?;
+        writeln!(out, "    </Processing>")?;
+        writeln!(out, "  </Description>")?;
+        writeln!(out, "  <Layout>")?;
+
+        for (page_index, page) in self.pages.iter().enumerate() {
+            let p = page_index + 1;
+            let page_box = BoundingBox::new(0.0, 0.0, page.width as f64, page.height as f64);
+            writeln!(
+                out,
+                r#"    <Page ID="page_{}" PHYSICAL_IMG_NR="{}"{}>"#,
+                p,
+                page.number,
+                if page_box.is_empty() {
+                    String::new()
+                } else {
+                    format!(
+                        r#" WIDTH="{}" HEIGHT="{}""#,
+                        options.convert(page_box.width),
+                        options.convert(page_box.height)
+                    )
+                }
+            )?;
+            writeln!(out, r#"      <PrintSpace{}>"#, options.position(&page_box))?;
+            let (mut b, mut l, mut w) = (0, 0, 0);
+            for paragraph in page.blocks.iter().flat_map(|block| &block.paragraphs) {
+                b += 1;
+                writeln!(
+                    out,
+                    r#"        <TextBlock ID="block_{}_{}"{}>"#,
+                    p,
+                    b,
+                    options.position(&paragraph.bbox)
+                )?;
+                for line in &paragraph.lines {
+                    l += 1;
+                    writeln!(
+                        out,
+                        r#"          <TextLine ID="line_{}_{}"{}>"#,
+                        p,
+                        l,
+                        options.position(&line.bbox)
+                    )?;
+                    for (i, word) in line.words.iter().enumerate() {
+                        w += 1;
+                        if i > 0 {
+                            writeln!(out, "            <SP/>")?;
+                        }
+                        let confidence = if self.confidence_reported {
+                            format!(r#" WC="{:.2}""#, word.confidence.clamp(0.0, 1.0))
+                        } else {
+                            String::new()
+                        };
+                        writeln!(
+                            out,
+                            r#"            <String ID="string_{}_{}" CONTENT="{}"{}{}/>"#,
+                            p,
+                            w,
+                            escape(&word.text),
+                            options.position(&word.bbox),
+                            confidence
+                        )?;
+                    }
+                    writeln!(out, "          </TextLine>")?;
+                }
+                writeln!(out, "        </TextBlock>")?;
+            }
+            writeln!(out, "      </PrintSpace>")?;
+            writeln!(out, "    </Page>")?;
+        }
+
+        writeln!(out, "  </Layout>")?;
+        writeln!(out, "</alto>")
+    }
+}
@@ -6,18 +6,20 @@ use cidre::{
 };
 use image::DynamicImage;
 use image::GenericImageView;
-use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::OnceLock;
 use std::{ffi::c_void, ptr::null_mut};
-use tracing::error;
 
-use crate::Language;
+use crate::backend::{run_blocking, Capabilities, OcrBackend};
+use crate::error::{OcrError, Result};
+use crate::result::{BoundingBox, Line, OcrResult, Word};
+use crate::{Language, OcrOptions};
+use async_trait::async_trait;
 
 static APPLE_LANGUAGE_MAP: OnceLock<HashMap<Language, &'static str>> = OnceLock::new();
 
-pub fn get_apple_languages(languages: &[Language]) -> Vec<String> {
-    let map = APPLE_LANGUAGE_MAP.get_or_init(|| {
+fn apple_language_map() -> &'static HashMap<Language, &'static str> {
+    APPLE_LANGUAGE_MAP.get_or_init(|| {
         let mut m = HashMap::new();
         m.insert(Language::English, "en-US");
         m.insert(Language::Spanish, "es-ES");
@@ -33,34 +35,43 @@ pub fn get_apple_languages(languages: &[Language]) -> Vec<String> {
         m.insert(Language::Thai, "th-TH");
         m.insert(Language::Arabic, "ar-SA");
         m
-    });
+    })
+}
 
+pub fn get_apple_languages(languages: &[Language]) -> Vec<String> {
+    let map = apple_language_map();
     languages
         .iter()
         .filter_map(|lang| map.get(lang).map(|&s| s.to_string()))
         .collect()
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-struct OcrResultBBox {
-    x: f64,
-    y: f64,
-    height: f64,
-    width: f64,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-struct OcrTextElement {
-    bounding_box: Vec<OcrResultBBox>,
-    confidence: f32,
-    text: String,
-}
+/// Backend using the Vision framework on the blocking pool.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AppleBackend;
+
+#[async_trait]
+impl OcrBackend for AppleBackend {
+    fn name(&self) -> &str {
+        "apple-vision"
+    }
+
+    fn supported_languages(&self) -> Vec<Language> {
+        apple_language_map().keys().copied().collect()
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            word_boxes: true,
+            confidence: true,
+            blocking: true,
+        }
+    }
 
-#[derive(Serialize, Deserialize, Debug)]
-struct OcrResult {
-    ocr_result: String,
-    text_elements: Vec<OcrTextElement>,
-    overall_confidence: f32,
+    async fn recognize(&self, image: &DynamicImage, options: &OcrOptions) -> Result<OcrResult> {
+        let (image, languages) = (image.clone(), options.languages.clone());
+        run_blocking(move || perform_ocr_apple(&image, &languages)).await
+    }
 }
 
 #[no_mangle]
@@ -70,10 +81,7 @@ extern "C" fn release_callback(_refcon: *mut c_void, _data_ptr: *const *const c_
 }
 
 #[cfg(target_os = "macos")]
-pub fn perform_ocr_apple(
-    image: &DynamicImage,
-    languages: &[Language],
-) -> (String, String, Option<f64>) {
+pub fn perform_ocr_apple(image: &DynamicImage, languages: &[Language]) -> Result<OcrResult> {
     cidre::objc::ar_pool(|| {
         // Convert languages to Apple format and create ns::Array
         let apple_languages = get_apple_languages(languages);
@@ -86,25 +94,22 @@ pub fn perform_ocr_apple(
         let rgb = image.grayscale().to_luma8();
         let raw_data = rgb.as_raw();
 
-        let mut overall_confidence = 0.0;
-        let default_ocr_result = (
-            String::from(""),
-            String::from("[]"),
-            Some(overall_confidence),
-        );
+        if width == 0 || height == 0 {
+            return Err(OcrError::InvalidImage("image has no pixels".to_string()));
+        }
 
-        let width = usize::try_from(width).unwrap();
-        let height = usize::try_from(height).unwrap();
+        let buf_width = width as usize;
+        let buf_height = height as usize;
 
         let mut pixel_buf_out = None;
 
         let pixel_buf = unsafe {
             PixelBuf::create_with_bytes_in(
-                width,
-                height,
+                buf_width,
+                buf_height,
                 PixelFormat::ONE_COMPONENT_8,
                 raw_data.as_ptr() as *mut c_void,
-                width,
+                buf_width,
                 release_callback,
                 null_mut(),
                 None,
@@ -113,65 +118,74 @@ pub fn perform_ocr_apple(
             )
             .to_result_unchecked(pixel_buf_out)
         }
-        .unwrap();
+        .map_err(|e| OcrError::Backend(format!("failed to create pixel buffer: {:?}", e)))?;
 
-        let handler = ImageRequestHandler::with_cv_pixel_buf(&pixel_buf, None).unwrap();
+        let handler =
+            ImageRequestHandler::with_cv_pixel_buf(&pixel_buf, None).ok_or_else(|| {
+                OcrError::Backend("failed to create Vision request handler".to_string())
+            })?;
         let mut request = RecognizeTextRequest::new();
         request.set_recognition_langs(&languages_array);
         request.set_uses_lang_correction(false);
         let requests = ns::Array::<vn::Request>::from_slice(&[&request]);
-        let result = handler.perform(&requests);
-
-        if result.is_err() {
-            return default_ocr_result;
-        }
+        handler
+            .perform(&requests)
+            .map_err(|e| OcrError::Backend(format!("Vision request failed: {:?}", e)))?;
+
+        let Some(results) = request.results() else {
+            return Ok(OcrResult::from_lines(width, height, Vec::new()));
+        };
+
+        let mut lines = Vec::with_capacity(results.len());
+        results.iter().for_each(|result| {
+            let Ok(observation_result) = result.top_candidates(1).get(0) else {
+                return;
+            };
+            let text = observation_result.string();
+            let confidence = observation_result.confidence();
+            let text = text.to_string();
+
+            // Vision reports one candidate per line; split it into words and ask Vision for
+            // the box of each word's character range.
+            let mut words = Vec::new();
+            let mut utf16_offset = 0usize;
+            let mut last_end = 0usize;
+            for (start, word) in word_spans(&text) {
+                utf16_offset += text[last_end..start].encode_utf16().count();
+                let word_len = word.encode_utf16().count();
+                let bbox = observation_result
+                    .bounding_box_for_range(ns::Range::new(utf16_offset, word_len))
+                    .map(|rect| vision_to_pixels(rect.bounding_box(), width, height))
+                    .unwrap_or_default();
+                words.push(Word::new(word, bbox, confidence));
+                utf16_offset += word_len;
+                last_end = start + word.len();
+            }
 
-        if let Some(results) = request.results() {
-            if !results.is_empty() {
-                let mut ocr_results_vec: Vec<serde_json::Value> = Vec::new();
-                let mut ocr_text: String = String::new();
-                results.iter().for_each(|result| {
-                    let observation_result = result.top_candidates(1).get(0).unwrap();
-                    let text = observation_result.string();
-                    let confidence = observation_result.confidence() as f64;
-                    let bbox = observation_result
-                        .bounding_box_for_range(ns::Range::new(0, text.len()))
-                        .unwrap()
-                        .bounding_box();
-                    let x = bbox.origin.x;
-                    let y = bbox.origin.y;
-                    let height = bbox.size.height;
-                    let width = bbox.size.width;
-
-                    ocr_results_vec.push(serde_json::json!({
-                        "level": "0",
-                        "page_num": "0",
-                        "block_num": "0",
-                        "par_num": "0",
-                        "line_num": "0",
-                        "word_num": "0",
-                        "left": x.to_string(),
-                        "top": y.to_string(),
-                        "width": width.to_string(),
-                        "height": height.to_string(),
-                        "conf": confidence.to_string(),
-                        "text": text.to_string(),
-                    }));
-
-                    overall_confidence += confidence;
-                    ocr_text.push_str(text.to_string().as_str());
-                });
-
-                let json_output_string =
-                    serde_json::to_string(&ocr_results_vec).unwrap_or_else(|e| {
-                        error!("Failed to serialize JSON output: {}", e);
-                        "[]".to_string()
-                    });
-
-                return (ocr_text, json_output_string, Some(overall_confidence));
+            if !words.is_empty() {
+                lines.push(Line::from_words(words));
             }
-        }
+        });
 
-        default_ocr_result
+        Ok(OcrResult::from_lines(width, height, lines))
     })
 }
+
+/// Vision reports normalized boxes with a bottom-left origin; flip and scale them to the
+/// crate's pixel, top-left convention.
+#[cfg(target_os = "macos")]
+fn vision_to_pixels(rect: cidre::cg::Rect, width: u32, height: u32) -> BoundingBox {
+    let normalized = BoundingBox::new(
+        rect.origin.x,
+        1.0 - rect.origin.y - rect.size.height,
+        rect.size.width,
+        rect.size.height,
+    );
+    BoundingBox::from_normalized(&normalized, width, height)
+}
+
+/// Byte offset and text of every whitespace-separated word in `text`.
+fn word_spans(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.split_whitespace()
+        .map(move |word| (word.as_ptr() as usize - text.as_ptr() as usize, word))
+}
@@ -0,0 +1,160 @@
+use async_trait::async_trait;
+use image::DynamicImage;
+use std::cell::RefCell;
+use std::fmt;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::sync::OwnedSemaphorePermit;
+
+use crate::error::{OcrError, Result};
+use crate::result::OcrResult;
+use crate::{Language, OcrOptions};
+
+/// What a backend can report besides plain text.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Capabilities {
+    /// Reports a bounding box for every word.
+    pub word_boxes: bool,
+    /// Reports real confidence scores rather than synthesized ones.
+    pub confidence: bool,
+    /// Recognition is CPU- or subprocess-bound. The engine counts these calls against its
+    /// concurrency limit; such backends should do their work through [`run_blocking`].
+    pub blocking: bool,
+}
+
+/// Result of checking whether a provider works on this machine.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProviderStatus {
+    pub name: String,
+    pub available: bool,
+    /// Engine version, when the backend exposes one.
+    pub version: Option<String>,
+    /// Languages that can actually be recognized right now.
+    pub languages: Vec<Language>,
+    /// Why the provider is unavailable, or other setup hints.
+    pub detail: Option<String>,
+}
+
+impl ProviderStatus {
+    pub fn available(name: impl Into<String>, languages: Vec<Language>) -> Self {
+        Self {
+            name: name.into(),
+            available: true,
+            version: None,
+            languages,
+            detail: None,
+        }
+    }
+
+    pub fn unavailable(name: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            available: false,
+            version: None,
+            languages: Vec::new(),
+            detail: Some(detail.into()),
+        }
+    }
+}
+
+/// A recognition engine usable by [`crate::OcrEngine`].
+///
+/// The built-in providers implement this trait, and your own engines can too; pass them as
+/// [`crate::OcrProvider::Custom`]. Results must follow the [`OcrResult`] conventions: pixel
+/// boxes with a top-left origin and 0–1 confidences.
+#[async_trait]
+pub trait OcrBackend: Send + Sync {
+    /// Short, stable identifier such as `"tesseract"`.
+    fn name(&self) -> &str;
+
+    fn supported_languages(&self) -> Vec<Language>;
+
+    fn capabilities(&self) -> Capabilities;
+
+    /// Checks that the backend can run here. The default assumes it always can.
+    async fn probe(&self) -> ProviderStatus {
+        ProviderStatus::available(self.name(), self.supported_languages())
+    }
+
+    async fn recognize(&self, image: &DynamicImage, options: &OcrOptions) -> Result<OcrResult>;
+}
+
+impl fmt::Debug for dyn OcrBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OcrBackend")
+            .field("name", &self.name())
+            .finish()
+    }
+}
+
+tokio::task_local! {
+    /// Concurrency permit the engine acquired for the current blocking recognition.
+    static PERMIT: Arc<OwnedSemaphorePermit>;
+}
+
+thread_local! {
+    /// Cancellation flag of the [`run_blocking`] task running on this thread.
+    static CANCELLED: RefCell<Option<Arc<AtomicBool>>> = const { RefCell::new(None) };
+}
+
+/// Whether nobody waits any longer for the [`run_blocking`] task running on this thread,
+/// because its future was dropped or timed out. Long-running work polls it to stop early.
+pub fn cancelled() -> bool {
+    CANCELLED.with(|flag| {
+        flag.borrow()
+            .as_ref()
+            .is_some_and(|flag| flag.load(Ordering::Relaxed))
+    })
+}
+
+/// Raises a cancellation flag when dropped.
+struct CancelOnDrop(Arc<AtomicBool>);
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Clears this thread's cancellation flag when dropped, as blocking threads are reused.
+struct ClearOnDrop;
+
+impl Drop for ClearOnDrop {
+    fn drop(&mut self) {
+        CANCELLED.with(|flag| flag.replace(None));
+    }
+}
+
+/// Runs `recognize`, a blocking backend's recognition, holding `permit` until it returns and
+/// every piece of work it handed to [`run_blocking`] finishes.
+pub(crate) async fn with_permit<F: Future>(
+    permit: OwnedSemaphorePermit,
+    recognize: F,
+) -> F::Output {
+    PERMIT.scope(Arc::new(permit), recognize).await
+}
+
+/// Runs a synchronous recognition, or a step of one, on tokio's blocking pool.
+///
+/// Called from [`OcrBackend::recognize`], the blocking task shares the engine's concurrency
+/// permit, so it stays held until the work finishes, even if the caller stops waiting. A
+/// backend may call it several times in one recognition; all calls count as one.
+/// Once nobody waits, [`cancelled`] turns true inside `recognize`.
+pub async fn run_blocking<F, T>(recognize: F) -> Result<T>
+where
+    F: FnOnce() -> Result<T> + Send + 'static,
+    T: Send + 'static,
+{
+    let permit = PERMIT.try_with(Arc::clone).ok();
+    let flag = Arc::new(AtomicBool::new(false));
+    let _cancel = CancelOnDrop(flag.clone());
+    tokio::task::spawn_blocking(move || {
+        let _permit = permit;
+        CANCELLED.with(|cancelled| cancelled.replace(Some(flag)));
+        let _clear = ClearOnDrop;
+        recognize()
+    })
+    .await
+    .map_err(|e| OcrError::Backend(format!("OCR task failed: {}", e)))?
+}
@@ -0,0 +1,74 @@
+/// The ten ASCII digits, used by [`crate::OcrOptions::digits_only`].
+pub const DIGITS: &str = "0123456789";
+
+/// Characters commonly mistaken for one another, most likely replacement first.
+const CONFUSIONS: &[(char, &[char])] = &[
+    ('O', &['0', 'D', 'Q']),
+    ('o', &['O', '0']),
+    ('D', &['0', 'O']),
+    ('Q', &['0', 'O']),
+    ('0', &['O', 'o', 'D']),
+    ('l', &['1', 'I', '|']),
+    ('I', &['1', 'l', '|']),
+    ('i', &['I', '1', 'l']),
+    ('|', &['1', 'l', 'I']),
+    ('!', &['1', 'l', 'I']),
+    ('1', &['l', 'I', '|']),
+    ('Z', &['2']),
+    ('z', &['Z', '2']),
+    ('2', &['Z', 'z']),
+    ('S', &['5']),
+    ('s', &['S', '5']),
+    ('5', &['S', 's']),
+    ('G', &['6']),
+    ('b', &['B', '6']),
+    ('6', &['G', 'b']),
+    ('T', &['7']),
+    ('7', &['T']),
+    ('B', &['8']),
+    ('8', &['B']),
+    ('g', &['G', '9', 'q']),
+    ('q', &['Q', '9', 'g']),
+    ('9', &['g', 'q']),
+    (',', &['.']),
+    ('_', &['-']),
+    ('—', &['-']),
+    ('–', &['-']),
+];
+
+/// Characters a result may contain, see [`crate::OcrResult::restrict_characters`].
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct CharacterFilter<'a> {
+    pub allowed: Option<&'a str>,
+    pub denied: Option<&'a str>,
+}
+
+impl CharacterFilter<'_> {
+    fn permits(&self, c: char) -> bool {
+        self.allowed.is_none_or(|allowed| allowed.contains(c))
+            && !self.denied.is_some_and(|denied| denied.contains(c))
+    }
+
+    /// `c` itself if permitted, otherwise the most likely permitted look-alike, if any.
+    fn map(&self, c: char) -> Option<char> {
+        if self.permits(c) {
+            return Some(c);
+        }
+        let swapped_case = if c.is_lowercase() {
+            c.to_uppercase().next()
+        } else {
+            c.to_lowercase().next()
+        };
+        CONFUSIONS
+            .iter()
+            .find(|(from, _)| *from == c)
+            .into_iter()
+            .flat_map(|(_, to)| to.iter().copied())
+            .chain(swapped_case)
+            .find(|&candidate| self.permits(candidate))
+    }
+
+    pub fn apply(&self, text: &str) -> String {
+        text.chars().filter_map(|c| self.map(c)).collect()
+    }
+}
@@ -1,13 +1,19 @@
+use crate::backend::{Capabilities, OcrBackend, ProviderStatus};
+use crate::error::{OcrError, Result};
 use crate::language::Language;
-use anyhow::Result;
+use crate::result::{BoundingBox, Line, OcrResult, Word};
+use crate::OcrOptions;
+use async_trait::async_trait;
 use base64::{engine::general_purpose, Engine as _};
-use image::DynamicImage;
+use image::{DynamicImage, GenericImageView};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Credentials {
     pub api_url: String,
     pub api_key: String,
+    /// Request timeout of [`perform_ocr_custom`] and provider probes. Through
+    /// [`crate::OcrEngine`], requests use [`OcrOptions::timeout`] instead.
     pub timeout_ms: u64,
 }
 
@@ -21,11 +27,84 @@ impl Default for Credentials {
     }
 }
 
+/// Backend posting images to an HTTP OCR service described by [`Credentials`].
+#[derive(Debug, Clone)]
+pub struct HttpBackend {
+    pub credentials: Credentials,
+}
+
+impl HttpBackend {
+    pub fn new(credentials: Credentials) -> Self {
+        Self { credentials }
+    }
+}
+
+#[async_trait]
+impl OcrBackend for HttpBackend {
+    fn name(&self) -> &str {
+        "http"
+    }
+
+    /// The service decides what it supports; every language is forwarded.
+    fn supported_languages(&self) -> Vec<Language> {
+        Language::all().to_vec()
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            word_boxes: false,
+            confidence: true,
+            blocking: false,
+        }
+    }
+
+    /// The endpoint counts as reachable if it answers at all, even with an error status.
+    async fn probe(&self) -> ProviderStatus {
+        let client = match reqwest::Client::builder()
+            .timeout(std::time::Duration::from_millis(
+                self.credentials.timeout_ms,
+            ))
+            .build()
+        {
+            Ok(client) => client,
+            Err(err) => return ProviderStatus::unavailable(self.name(), err.to_string()),
+        };
+        match client.head(&self.credentials.api_url).send().await {
+            Ok(_) => ProviderStatus::available(self.name(), self.supported_languages()),
+            Err(err) => ProviderStatus::unavailable(
+                self.name(),
+                format!("{} is unreachable: {}", self.credentials.api_url, err),
+            ),
+        }
+    }
+
+    async fn recognize(&self, image: &DynamicImage, options: &OcrOptions) -> Result<OcrResult> {
+        post_image(
+            image,
+            &options.languages,
+            &self.credentials,
+            options.timeout,
+        )
+        .await
+    }
+}
+
 pub async fn perform_ocr_custom(
     image: &DynamicImage,
     languages: Vec<Language>,
     credentials: &Credentials,
-) -> Result<(String, String, Option<f64>)> {
+) -> Result<OcrResult> {
+    let timeout = std::time::Duration::from_millis(credentials.timeout_ms);
+    post_image(image, &languages, credentials, timeout).await
+}
+
+/// Posts `image` to the service, failing with [`OcrError::Timeout`] after `timeout`.
+async fn post_image(
+    image: &DynamicImage,
+    languages: &[Language],
+    credentials: &Credentials,
+    timeout: std::time::Duration,
+) -> Result<OcrResult> {
     // Convert image to RGB before encoding to JPEG
     let rgb_image = image.to_rgb8();
 
@@ -44,9 +123,14 @@ pub async fn perform_ocr_custom(
     });
 
     // Create client with timeout
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_millis(credentials.timeout_ms))
-        .build()?;
+    let client = reqwest::Client::builder().timeout(timeout).build()?;
+    let map_err = |err: reqwest::Error| {
+        if err.is_timeout() {
+            OcrError::Timeout(timeout)
+        } else {
+            OcrError::from(err)
+        }
+    };
 
     // Make the API request
     let response = client
@@ -54,21 +138,49 @@ pub async fn perform_ocr_custom(
         .header("Authorization", format!("Bearer {}", credentials.api_key))
         .json(&payload)
         .send()
-        .await?;
+        .await
+        .map_err(map_err)?;
+
+    let status = response.status();
+    if !status.is_success() {
+        return Err(OcrError::Http {
+            status: status.as_u16(),
+        });
+    }
 
     // Handle the response
-    let ocr_result: OcrResponse = response.json().await?;
+    let ocr_response: OcrResponse = response.json().await.map_err(map_err)?;
 
-    Ok((
-        ocr_result.text,
-        ocr_result.structured_data.to_string(),
-        Some(ocr_result.confidence),
-    ))
+    let (width, height) = image.dimensions();
+    Ok(response_to_result(ocr_response, width, height))
 }
 
 #[derive(Debug, Deserialize)]
 struct OcrResponse {
     text: String,
-    structured_data: serde_json::Value,
     confidence: f64,
+    /// Servers that already speak the `OcrResult` model can return it directly.
+    #[serde(default)]
+    result: Option<OcrResult>,
+}
+
+/// The plain-text response carries no geometry, so every word gets the overall confidence
+/// and an empty box.
+fn response_to_result(response: OcrResponse, width: u32, height: u32) -> OcrResult {
+    if let Some(result) = response.result {
+        return result;
+    }
+    let lines = response
+        .text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            Line::from_words(
+                line.split_whitespace()
+                    .map(|word| Word::new(word, BoundingBox::default(), response.confidence as f32))
+                    .collect(),
+            )
+        })
+        .collect();
+    OcrResult::from_lines(width, height, lines)
 }
@@ -0,0 +1,67 @@
+use std::time::Duration;
+
+use thiserror::Error;
+
+/// Errors returned by every recognition path of the crate.
+#[derive(Debug, Error)]
+pub enum OcrError {
+    /// The backend cannot run here: wrong platform, missing binary or unreachable service.
+    #[error("OCR provider unavailable: {0}")]
+    ProviderUnavailable(String),
+    #[error("language not installed: {0}")]
+    LanguageNotInstalled(String),
+    #[error("OCR timed out after {0:?}")]
+    Timeout(Duration),
+    #[error("invalid image: {0}")]
+    InvalidImage(String),
+    #[error("OCR service returned HTTP {status}")]
+    Http { status: u16 },
+    /// The backend answered but its output could not be parsed.
+    #[error("failed to decode OCR output: {0}")]
+    Decode(String),
+    #[error("OCR backend error: {0}")]
+    Backend(String),
+}
+
+pub type Result<T, E = OcrError> = std::result::Result<T, E>;
+
+impl From<image::ImageError> for OcrError {
+    fn from(err: image::ImageError) -> Self {
+        OcrError::InvalidImage(err.to_string())
+    }
+}
+
+impl From<std::io::Error> for OcrError {
+    fn from(err: std::io::Error) -> Self {
+        OcrError::Backend(err.to_string())
+    }
+}
+
+impl From<lopdf::Error> for OcrError {
+    fn from(err: lopdf::Error) -> Self {
+        OcrError::Backend(format!("PDF error: {}", err))
+    }
+}
+
+impl From<reqwest::Error> for OcrError {
+    fn from(err: reqwest::Error) -> Self {
+        if let Some(status) = err.status() {
+            OcrError::Http {
+                status: status.as_u16(),
+            }
+        } else if err.is_decode() {
+            OcrError::Decode(err.to_string())
+        } else if err.is_connect() {
+            OcrError::ProviderUnavailable(err.to_string())
+        } else {
+            OcrError::Backend(err.to_string())
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+impl From<windows::core::Error> for OcrError {
+    fn from(err: windows::core::Error) -> Self {
+        OcrError::Backend(err.to_string())
+    }
+}
@@ -0,0 +1,137 @@
+use std::fmt::Write;
+
+use crate::result::{BoundingBox, OcrResult};
+use crate::xml::escape;
+
+impl OcrResult {
+    /// Serializes the result as an hOCR 1.2 document (XHTML), with `ocr_page`, `ocr_carea`,
+    /// `ocr_par`, `ocr_line` and `ocrx_word` elements.
+    ///
+    /// Elements carry a `bbox` in pixels, and words an `x_wconf` of 0–100 when the provider
+    /// reports confidence. Boxes are left out for providers that report none.
+    pub fn to_hocr(&self) -> String {
+        let mut hocr = String::new();
+        // Writing to a String cannot fail
+        let _ = self.write_hocr(&mut hocr);
+        hocr
+    }
+
+    fn write_hocr(&self, out: &mut String) -> std::fmt::Result {
+        let system = match &self.provider {
+            Some(provider) => format!("uni-ocr {} ({})", env!("CARGO_PKG_VERSION"), provider),
+            None => format!("uni-ocr {}", env!("CARGO_PKG_VERSION")),
+        };
+        writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(
+            out,
+            r#"<!DOCTYPE html PUBLIC "-//W3C//DTD XHTML 1.0 Transitional//EN" "http://www.w3.org/TR/xhtml1/DTD/xhtml1-transitional.dtd">"#
+        )?;
+        writeln!(out, r#"<html xmlns="http://www.w3.org/1999/xhtml">"#)?;
+        writeln!(out, " <head>")?;
+        writeln!(out, "  <title></title>")?;
+        writeln!(
+            out,
+            r#"  <meta http-equiv="Content-Type" content="text/html;charset=utf-8"/>"#
+        )?;
+        writeln!(
+            out,
+            r#"  <meta name="ocr-system" content="{}"/>"#,
+            escape(&system)
+        )?;
+        writeln!(
+            out,
+            r#"  <meta name="ocr-capabilities" content="ocr_page ocr_carea ocr_par ocr_line ocrx_word ocrp_wconf"/>"#
+        )?;
+        writeln!(out, " </head>")?;
+        writeln!(out, " <body>")?;
+
+        for (page_index, page) in self.pages.iter().enumerate() {
+            let p = page_index + 1;
+            let page_box = BoundingBox::new(0.0, 0.0, page.width as f64, page.height as f64);
+            writeln!(
+                out,
+                r#"  <div class="ocr_page" id="page_{}" title="{}; ppageno {}">"#,
+                p,
+                bbox(&page_box),
+                page.number.saturating_sub(1)
+            )?;
+            let (mut b, mut par, mut l, mut w) = (0, 0, 0, 0);
+            for block in &page.blocks {
+                b += 1;
+                writeln!(
+                    out,
+                    r#"   <div class="ocr_carea" id="block_{}_{}"{}>"#,
+                    p,
+                    b,
+                    title(&block.bbox, None)
+                )?;
+                for paragraph in &block.paragraphs {
+                    par += 1;
+                    writeln!(
+                        out,
+                        r#"    <p class="ocr_par" id="par_{}_{}"{}>"#,
+                        p,
+                        par,
+                        title(&paragraph.bbox, None)
+                    )?;
+                    for line in &paragraph.lines {
+                        l += 1;
+                        writeln!(
+                            out,
+                            r#"     <span class="ocr_line" id="line_{}_{}"{}>"#,
+                            p,
+                            l,
+                            title(&line.bbox, None)
+                        )?;
+                        for word in &line.words {
+                            w += 1;
+                            let confidence = self.confidence_reported.then_some(word.confidence);
+                            writeln!(
+                                out,
+                                r#"      <span class="ocrx_word" id="word_{}_{}"{}>{}</span>"#,
+                                p,
+                                w,
+                                title(&word.bbox, confidence),
+                                escape(&word.text)
+                            )?;
+                        }
+                        writeln!(out, "     </span>")?;
+                    }
+                    writeln!(out, "    </p>")?;
+                }
+                writeln!(out, "   </div>")?;
+            }
+            writeln!(out, "  </div>")?;
+        }
+
+        writeln!(out, " </body>")?;
+        writeln!(out, "</html>")
+    }
+}
+
+/// `bbox x0 y0 x1 y1`, in whole pixels.
+fn bbox(b: &BoundingBox) -> String {
+    format!(
+        "bbox {} {} {} {}",
+        b.x.round() as i64,
+        b.y.round() as i64,
+        b.right().round() as i64,
+        b.bottom().round() as i64
+    )
+}
+
+/// The `title` attribute with an element's properties, or nothing when it has none.
+fn title(b: &BoundingBox, confidence: Option<f32>) -> String {
+    let mut properties = Vec::new();
+    if !b.is_empty() {
+        properties.push(bbox(b));
+    }
+    if let Some(confidence) = confidence {
+        properties.push(format!("x_wconf {}", (confidence * 100.0).round() as i64));
+    }
+    if properties.is_empty() {
+        String::new()
+    } else {
+        format!(r#" title="{}""#, properties.join("; "))
+    }
+}
@@ -11,6 +11,11 @@ macro_rules! languages {
         }
 
         impl Language {
+            /// Every supported language.
+            pub fn all() -> &'static [Language] {
+                &[$(Language::$name),*]
+            }
+
             pub fn as_lang_code(&self) -> &'static str {
                 match self {
                     $(Language::$name => $iso),*
@@ -23,6 +28,14 @@ macro_rules! languages {
                     $(Language::$name => $tess),*
                 }
             }
+
+            /// Inverse of [`Language::as_tesseract_code`], e.g. `"chi_sim"` → Chinese.
+            pub fn from_tesseract_code(code: &str) -> Option<Language> {
+                Language::all()
+                    .iter()
+                    .copied()
+                    .find(|lang| lang.as_tesseract_code() == Some(code))
+            }
         }
     };
 }
@@ -91,11 +104,11 @@ languages! {
     Yiddish => ("yi", Some("yid")),
     Lao => ("lo", Some("lao")),
     Uzbek => ("uz", Some("uzb")),
-    Faroese => ("fo", Some("fo")),
+    Faroese => ("fo", Some("fao")),
     Pashto => ("ps", Some("pus")),
     Maltese => ("mt", Some("mlt")),
     Sanskrit => ("sa", Some("san")),
-    Luxembourgish => ("lb", Some("lb")),
+    Luxembourgish => ("lb", Some("ltz")),
     Myanmar => ("my", Some("mya")),
     Tibetan => ("bo", Some("bod")),
     Tagalog => ("tl", Some("tgl")),
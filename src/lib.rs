@@ -1,7 +1,23 @@
-use anyhow::Result;
-pub use custom_ocr::Credentials;
+pub use alto::{AltoOptions, MeasurementUnit};
+pub use backend::{cancelled, run_blocking, Capabilities, OcrBackend, ProviderStatus};
+pub use custom_ocr::{Credentials, HttpBackend};
+pub use error::{OcrError, Result};
+use futures_util::future::join_all;
+use futures_util::stream::{self, Stream, StreamExt};
 use image::DynamicImage;
+pub use pdf::PdfWriter;
+use pdf::{PdfPage, PdfReader};
+pub use preprocess::{Binarization, Preprocessor};
+pub use region::Rect;
+pub use result::{Block, BoundingBox, Line, OcrResult, Page, Paragraph, Word};
+pub use source::ImageSource;
+use std::path::Path;
+use std::sync::Arc;
 use std::time::Duration;
+use tiling::Tile;
+pub use tiling::Tiling;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tracing::debug;
 
 #[derive(Debug, Clone)]
 pub enum OcrProvider {
@@ -9,14 +25,143 @@ pub enum OcrProvider {
     MacOS,
     Windows,
     Tesseract,
-    Custom { credentials: Credentials },
+    /// An HTTP OCR service, see [`custom_ocr`].
+    Http {
+        credentials: Credentials,
+    },
+    /// Any engine implementing [`OcrBackend`].
+    Custom(Arc<dyn OcrBackend>),
+}
+
+impl OcrProvider {
+    /// Providers [`OcrProvider::Auto`] tries, in order, unless the engine was given its own
+    /// chain with [`OcrEngine::with_fallback_chain`].
+    pub fn default_chain() -> Vec<OcrProvider> {
+        #[cfg(target_os = "macos")]
+        {
+            vec![OcrProvider::MacOS, OcrProvider::Tesseract]
+        }
+        #[cfg(target_os = "windows")]
+        {
+            vec![OcrProvider::Windows, OcrProvider::Tesseract]
+        }
+        #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+        {
+            vec![OcrProvider::Tesseract]
+        }
+    }
+
+    /// Checks whether the provider works on this machine: executables and their versions,
+    /// installed languages, endpoint reachability.
+    ///
+    /// [`OcrProvider::Auto`] reports the first provider of [`OcrProvider::default_chain`] that
+    /// works. An engine with its own chain is probed with [`OcrEngine::probe`].
+    pub async fn probe(&self) -> ProviderStatus {
+        match self {
+            OcrProvider::Auto => probe_chain(&OcrProvider::default_chain()).await,
+            provider => probe_backend(provider).await,
+        }
+    }
+
+    /// Resolves the provider to the backend that serves it on this platform.
+    ///
+    /// [`OcrProvider::Auto`] resolves to the first provider of [`OcrProvider::default_chain`]
+    /// available on this platform.
+    pub fn backend(&self) -> Result<Arc<dyn OcrBackend>> {
+        match self {
+            OcrProvider::MacOS => {
+                #[cfg(target_os = "macos")]
+                {
+                    Ok(Arc::new(AppleBackend))
+                }
+                #[cfg(not(target_os = "macos"))]
+                {
+                    Err(OcrError::ProviderUnavailable(
+                        "macOS OCR is not available on this platform".to_string(),
+                    ))
+                }
+            }
+            OcrProvider::Windows => {
+                #[cfg(target_os = "windows")]
+                {
+                    Ok(Arc::new(WindowsBackend))
+                }
+                #[cfg(not(target_os = "windows"))]
+                {
+                    Err(OcrError::ProviderUnavailable(
+                        "Windows OCR is not available on this platform".to_string(),
+                    ))
+                }
+            }
+            OcrProvider::Tesseract => Ok(Arc::new(TesseractBackend)),
+            OcrProvider::Http { credentials } => {
+                Ok(Arc::new(HttpBackend::new(credentials.clone())))
+            }
+            OcrProvider::Custom(backend) => Ok(backend.clone()),
+            OcrProvider::Auto => {
+                let mut last_error = None;
+                for provider in OcrProvider::default_chain() {
+                    match provider.backend() {
+                        Ok(backend) => return Ok(backend),
+                        Err(err) => last_error = Some(err),
+                    }
+                }
+                Err(last_error.unwrap_or_else(|| {
+                    OcrError::ProviderUnavailable("the fallback chain is empty".to_string())
+                }))
+            }
+        }
+    }
+}
+
+async fn probe_backend(provider: &OcrProvider) -> ProviderStatus {
+    match provider.backend() {
+        Ok(backend) => backend.probe().await,
+        Err(err) => ProviderStatus::unavailable(format!("{:?}", provider), err.to_string()),
+    }
+}
+
+/// Status of the first provider of `chain` that works here, the one [`OcrProvider::Auto`]
+/// starts with.
+async fn probe_chain(chain: &[OcrProvider]) -> ProviderStatus {
+    let mut details = Vec::new();
+    for provider in chain {
+        let status = probe_backend(provider).await;
+        if status.available {
+            return status;
+        }
+        details.push(format!(
+            "{}: {}",
+            status.name,
+            status.detail.unwrap_or_default()
+        ));
+    }
+    if details.is_empty() {
+        details.push("the fallback chain is empty".to_string());
+    }
+    ProviderStatus::unavailable("auto", details.join("; "))
 }
 
 #[derive(Debug, Clone)]
 pub struct OcrOptions {
     languages: Vec<Language>,
+    /// Minimum confidence, on a 0–1 scale, for a word or line to be kept in the result.
     confidence_threshold: f32,
     timeout: Duration,
+    /// Only these characters may appear in results. Tesseract restricts recognition itself;
+    /// other providers' results are remapped, see [`OcrResult::restrict_characters`].
+    allowed_characters: Option<String>,
+    /// These characters never appear in results.
+    denied_characters: Option<String>,
+    /// Image cleanup before recognition; by default only [`Preprocessor::auto_upscale`].
+    preprocess: Preprocessor,
+    /// Splits large images into tiles recognized in parallel; [`Tiling::default`] unless
+    /// turned off. Backends without [`Capabilities::word_boxes`] always get the whole image.
+    tiling: Option<Tiling>,
+    /// Settings only the Tesseract provider uses.
+    tesseract: TesseractOptions,
+    /// Resolution at which PDF pages without a text layer are rendered for recognition.
+    pdf_dpi: u32,
 }
 
 impl Default for OcrOptions {
@@ -25,6 +170,12 @@ impl Default for OcrOptions {
             languages: vec![Language::English],
             confidence_threshold: 0.0,
             timeout: Duration::from_secs(30),
+            allowed_characters: None,
+            denied_characters: None,
+            preprocess: Preprocessor::default().auto_upscale(true),
+            tiling: Some(Tiling::default()),
+            tesseract: TesseractOptions::default(),
+            pdf_dpi: 300,
         }
     }
 }
@@ -44,18 +195,86 @@ impl OcrOptions {
         self.timeout = timeout;
         self
     }
+
+    pub fn allowed_characters(mut self, chars: impl Into<String>) -> Self {
+        self.allowed_characters = Some(chars.into());
+        self
+    }
+
+    pub fn denied_characters(mut self, chars: impl Into<String>) -> Self {
+        self.denied_characters = Some(chars.into());
+        self
+    }
+
+    /// Restricts results to the digits 0–9, e.g. for amounts or counters.
+    pub fn digits_only(self) -> Self {
+        self.allowed_characters(charset::DIGITS)
+    }
+
+    /// Replaces the default preprocessing; `Preprocessor::default()` turns it off.
+    pub fn preprocess(mut self, preprocessor: Preprocessor) -> Self {
+        self.preprocess = preprocessor;
+        self
+    }
+
+    /// `None` recognizes large images in one piece.
+    pub fn tiling(mut self, tiling: Option<Tiling>) -> Self {
+        self.tiling = tiling;
+        self
+    }
+
+    pub fn tesseract(mut self, options: TesseractOptions) -> Self {
+        self.tesseract = options;
+        self
+    }
+
+    pub fn pdf_dpi(mut self, dpi: u32) -> Self {
+        self.pdf_dpi = dpi.max(1);
+        self
+    }
+
+    /// Languages to recognize, for custom backends to pass on to their engine.
+    pub fn get_languages(&self) -> &[Language] {
+        &self.languages
+    }
+
+    pub fn get_confidence_threshold(&self) -> f32 {
+        self.confidence_threshold
+    }
+
+    pub fn get_timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    pub fn get_allowed_characters(&self) -> Option<&str> {
+        self.allowed_characters.as_deref()
+    }
+
+    pub fn get_denied_characters(&self) -> Option<&str> {
+        self.denied_characters.as_deref()
+    }
 }
 
 pub struct OcrEngine {
     provider: OcrProvider,
     options: OcrOptions,
+    max_concurrency: usize,
+    /// Bounds how many CPU- or subprocess-bound recognitions run on the blocking pool at once.
+    blocking_permits: Arc<Semaphore>,
+    fallback_chain: Option<Vec<OcrProvider>>,
+    fallback_confidence: f64,
 }
 
 impl OcrEngine {
     pub fn new(provider: OcrProvider) -> Result<Self> {
+        let max_concurrency = default_concurrency();
         Ok(Self {
             provider,
             options: OcrOptions::default(),
+            max_concurrency,
+            blocking_permits: Arc::new(Semaphore::new(max_concurrency)),
+            fallback_chain: None,
+            fallback_confidence: 0.0,
         })
     }
 
@@ -64,87 +283,464 @@ impl OcrEngine {
         self
     }
 
-    pub async fn recognize_image(
-        &self,
-        image: &DynamicImage,
-    ) -> Result<(String, String, Option<f64>)> {
+    /// Maximum number of recognitions this engine runs in parallel: batch items in flight,
+    /// and calls into blocking backends (Tesseract, Vision and Windows OCR, or any backend
+    /// reporting [`Capabilities::blocking`]). Defaults to the number of available CPUs.
+    pub fn with_max_concurrency(mut self, limit: usize) -> Self {
+        self.max_concurrency = limit.max(1);
+        self.blocking_permits = Arc::new(Semaphore::new(self.max_concurrency));
+        self
+    }
+
+    /// Providers tried in order by [`OcrProvider::Auto`], replacing
+    /// [`OcrProvider::default_chain`]. For example `[Http { .. }, Tesseract]` uses a local
+    /// Tesseract whenever the service is down.
+    pub fn with_fallback_chain(mut self, chain: Vec<OcrProvider>) -> Self {
+        self.fallback_chain = Some(chain);
+        self
+    }
+
+    /// Minimum overall confidence (0–1) a fallback chain result needs to be accepted.
+    /// Below it the next provider is tried; if none reaches it, the most confident result
+    /// wins.
+    pub fn with_fallback_confidence(mut self, floor: f64) -> Self {
+        self.fallback_confidence = floor;
+        self
+    }
+
+    /// Checks whether this engine can recognize here. For [`OcrProvider::Auto`] it reports the
+    /// first provider of the engine's fallback chain that works, the one recognition starts
+    /// with.
+    pub async fn probe(&self) -> ProviderStatus {
         match &self.provider {
-            OcrProvider::MacOS => {
-                #[cfg(target_os = "macos")]
-                {
-                    Ok(perform_ocr_apple(image, &self.options.languages))
-                }
-                #[cfg(not(target_os = "macos"))]
-                {
-                    Err(anyhow::anyhow!(
-                        "macOS OCR is not available on this platform"
-                    ))
-                }
+            OcrProvider::Auto => probe_chain(&self.chain()).await,
+            provider => provider.probe().await,
+        }
+    }
+
+    /// Probes the built-in providers plus any HTTP or custom provider this engine is
+    /// configured with, and returns the status of those that work here.
+    pub async fn available_providers(&self) -> Vec<ProviderStatus> {
+        let mut candidates = vec![
+            OcrProvider::MacOS,
+            OcrProvider::Windows,
+            OcrProvider::Tesseract,
+        ];
+        let configured =
+            std::iter::once(&self.provider).chain(self.fallback_chain.iter().flatten());
+        candidates.extend(
+            configured
+                .filter(|p| matches!(p, OcrProvider::Http { .. } | OcrProvider::Custom(_)))
+                .cloned(),
+        );
+
+        join_all(candidates.iter().map(OcrProvider::probe))
+            .await
+            .into_iter()
+            .filter(|status| status.available)
+            .collect()
+    }
+
+    /// Recognizes `image` with the configured provider, walking the fallback chain for
+    /// [`OcrProvider::Auto`]. [`OcrResult::provider`] tells which backend produced the result.
+    ///
+    /// Preprocessing and each attempt fail with [`OcrError::Timeout`] when they take longer
+    /// than [`OcrOptions::timeout`].
+    pub async fn recognize_image(&self, image: &DynamicImage) -> Result<OcrResult> {
+        let preprocessor = &self.options.preprocess;
+        if preprocessor.is_empty() {
+            return self.recognize_tiled(image).await;
+        }
+
+        let prepared = {
+            let (image, options) = (image.clone(), self.options.clone());
+            let prepare =
+                backend::run_blocking(move || Ok(options.preprocess.prepare(&image, &options)));
+            // Counted like a blocking recognition, as orientation detection runs tesseract
+            let timeout = self.options.timeout;
+            let prepare = backend::with_permit(self.blocking_permit().await?, prepare);
+            match tokio::time::timeout(timeout, prepare).await {
+                Ok(prepared) => prepared?,
+                Err(_) => return Err(OcrError::Timeout(timeout)),
             }
-            OcrProvider::Windows => {
-                #[cfg(target_os = "windows")]
-                {
-                    perform_ocr_windows(image).await
+        };
+        let mut result = self.recognize_tiled(&prepared.image).await?;
+
+        let transform = prepared.transform;
+        result.map_boxes(|b| transform.original_box(b));
+        for page in &mut result.pages {
+            (page.width, page.height) = transform.dimensions();
+            page.rotation = transform.rotation();
+        }
+        Ok(result)
+    }
+
+    /// Providers recognition tries, in order.
+    fn chain(&self) -> Vec<OcrProvider> {
+        match &self.provider {
+            OcrProvider::Auto => self
+                .fallback_chain
+                .clone()
+                .unwrap_or_else(OcrProvider::default_chain),
+            provider => vec![provider.clone()],
+        }
+    }
+
+    /// Walks the provider chain with `image`, split into tiles when it is large. Every tile
+    /// goes to the same backend; if one fails, the whole image falls back to the next.
+    async fn recognize_tiled(&self, image: &DynamicImage) -> Result<OcrResult> {
+        let tiles = match &self.options.tiling {
+            Some(tiling) => tiling.tiles(image.width(), image.height()),
+            None => Vec::new(),
+        };
+        if tiles.len() > 1 {
+            debug!("recognizing {} tiles", tiles.len());
+        }
+        let chain = self.chain();
+
+        let mut best: Option<OcrResult> = None;
+        let mut last_error = None;
+        for provider in &chain {
+            let result = match provider.backend() {
+                Ok(backend) => self.recognize_tiles(backend, image, &tiles).await,
+                Err(err) => Err(err),
+            };
+            match result {
+                Ok(result) => {
+                    let confidence = result.confidence().unwrap_or(0.0);
+                    if confidence >= self.fallback_confidence {
+                        return Ok(result.filter_confidence(self.options.confidence_threshold));
+                    }
+                    debug!(
+                        "{:?} confidence {:.2} is below the fallback floor",
+                        result.provider, confidence
+                    );
+                    let best_confidence = best.as_ref().map(|b| b.confidence().unwrap_or(0.0));
+                    if best_confidence.is_none() || best_confidence < Some(confidence) {
+                        best = Some(result);
+                    }
                 }
-                #[cfg(not(target_os = "windows"))]
-                {
-                    Err(anyhow::anyhow!(
-                        "Windows OCR is not available on this platform"
-                    ))
+                // Every other provider would reject the same image
+                Err(err @ OcrError::InvalidImage(_)) => return Err(err),
+                Err(err) => {
+                    debug!("{:?} failed, trying the next provider: {}", provider, err);
+                    last_error = Some(err);
                 }
             }
-            OcrProvider::Tesseract => {
-                Ok(perform_ocr_tesseract(image, self.options.languages.clone()))
-            }
-            OcrProvider::Custom { credentials } => {
-                perform_ocr_custom(image, self.options.languages.clone(), &credentials).await
-            }
-            OcrProvider::Auto => {
-                #[cfg(target_os = "macos")]
-                {
-                    Ok(perform_ocr_apple(image, &self.options.languages))
-                }
-                #[cfg(target_os = "windows")]
-                {
-                    perform_ocr_windows(image).await
-                }
-                #[cfg(not(any(target_os = "macos", target_os = "windows")))]
-                {
-                    Ok(perform_ocr_tesseract(image, self.options.languages.clone()))
+        }
+
+        match (best, last_error) {
+            (Some(result), _) => Ok(result.filter_confidence(self.options.confidence_threshold)),
+            (None, Some(err)) => Err(err),
+            (None, None) => Err(OcrError::ProviderUnavailable(
+                "the fallback chain is empty".to_string(),
+            )),
+        }
+    }
+
+    /// Recognizes `image` with `backend`, its `tiles` concurrently when there are several,
+    /// and stitches the results together.
+    async fn recognize_tiles(
+        &self,
+        backend: Arc<dyn OcrBackend>,
+        image: &DynamicImage,
+        tiles: &[Tile],
+    ) -> Result<OcrResult> {
+        // Without word boxes, text read twice in an overlap cannot be told apart
+        if tiles.len() <= 1 || !backend.capabilities().word_boxes {
+            return self.recognize_with_backend(backend, image).await;
+        }
+
+        let results: Vec<Result<OcrResult>> = stream::iter(tiles)
+            .map(|tile| {
+                let backend = backend.clone();
+                async move {
+                    let Rect {
+                        x,
+                        y,
+                        width,
+                        height,
+                    } = tile.rect;
+                    self.recognize_with_backend(backend, &image.crop_imm(x, y, width, height))
+                        .await
                 }
-            }
+            })
+            .buffered(self.max_concurrency)
+            .collect()
+            .await;
+        let results = results.into_iter().collect::<Result<Vec<_>>>()?;
+        Ok(tiling::merge(
+            image.width(),
+            image.height(),
+            tiles.iter().copied().zip(results).collect(),
+        ))
+    }
+
+    async fn recognize_with_backend(
+        &self,
+        backend: Arc<dyn OcrBackend>,
+        image: &DynamicImage,
+    ) -> Result<OcrResult> {
+        let timeout = self.options.timeout;
+        let mut result =
+            match tokio::time::timeout(timeout, self.run_backend(&backend, image)).await {
+                Ok(result) => result?,
+                Err(_) => return Err(OcrError::Timeout(timeout)),
+            };
+        result.provider = Some(backend.name().to_string());
+        Ok(result.restrict_characters(
+            self.options.allowed_characters.as_deref(),
+            self.options.denied_characters.as_deref(),
+        ))
+    }
+
+    async fn run_backend(
+        &self,
+        backend: &Arc<dyn OcrBackend>,
+        image: &DynamicImage,
+    ) -> Result<OcrResult> {
+        if !backend.capabilities().blocking {
+            return backend.recognize(image, &self.options).await;
+        }
+        let permit = self.blocking_permit().await?;
+        backend::with_permit(permit, backend.recognize(image, &self.options)).await
+    }
+
+    /// Waits for a slot on the blocking pool. Owned, so that it can outlive the caller inside
+    /// the blocking task.
+    async fn blocking_permit(&self) -> Result<OwnedSemaphorePermit> {
+        self.blocking_permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|e| OcrError::Backend(e.to_string()))
+    }
+
+    pub async fn recognize_file(&self, path: &str) -> Result<OcrResult> {
+        self.recognize_source(ImageSource::from(path)).await
+    }
+
+    /// Recognizes an image file or an in-memory image. Files are decoded on the blocking pool.
+    ///
+    /// `.pdf` files go through [`OcrEngine::recognize_pdf`], and multi-page TIFFs and
+    /// animated GIFs and PNGs through [`OcrEngine::recognize_pages`], one page per frame.
+    pub async fn recognize_source(&self, source: ImageSource) -> Result<OcrResult> {
+        let mut frames = match source {
+            ImageSource::Image(image) => return self.recognize_image(&image).await,
+            ImageSource::Path(path) if pdf::is_pdf(&path) => return self.recognize_pdf(path).await,
+            source => tokio::task::spawn_blocking(move || source.load_frames())
+                .await
+                .map_err(|e| OcrError::Backend(format!("image decoding task failed: {}", e)))??,
+        };
+        match frames.len() {
+            1 => self.recognize_image(&frames.remove(0)).await,
+            _ => self.recognize_pages(&frames).await,
         }
     }
 
-    pub async fn recognize_file(&self, path: &str) -> Result<(String, String, Option<f64>)> {
-        let img = image::open(path)?;
-        self.recognize_image(&img).await
+    /// Recognizes every page of a PDF, in one result with a page per PDF page.
+    ///
+    /// Pages whose text layer covers their images, as OCR tools write it, return it as is.
+    /// Other pages are rendered at [`OcrOptions::pdf_dpi`] and recognized, up to
+    /// [`OcrEngine::with_max_concurrency`] at a time, and any text they also show is added as
+    /// one more block. Positions are in pixels at that resolution either way.
+    ///
+    /// Text-layer words get a confidence of 1, so `confidence_reported` is `false` when the
+    /// result has any.
+    pub async fn recognize_pdf(&self, path: impl AsRef<Path>) -> Result<OcrResult> {
+        let path = path.as_ref().to_path_buf();
+        let reader = tokio::task::spawn_blocking(move || PdfReader::open(&path))
+            .await
+            .map_err(|e| OcrError::Backend(format!("PDF reading task failed: {}", e)))??;
+        let reader = Arc::new(reader);
+        let dpi = self.options.pdf_dpi;
+
+        let results = stream::iter(0..reader.page_count())
+            .map(|index| {
+                let reader = reader.clone();
+                async move {
+                    let page = tokio::task::spawn_blocking(move || reader.page(index, dpi))
+                        .await
+                        .map_err(|e| {
+                            OcrError::Backend(format!("PDF rendering task failed: {}", e))
+                        })??;
+                    match page {
+                        PdfPage::Text(page) => Ok((page, None)),
+                        PdfPage::Image { image, text } => {
+                            let (mut page, mut recognized) = self.recognize_page(&image).await?;
+                            if !text.is_empty() {
+                                pdf::merge_text(&mut page, text);
+                                if let Some(recognized) = &mut recognized {
+                                    recognized.confidence_reported = false;
+                                }
+                            }
+                            Ok((page, recognized))
+                        }
+                    }
+                }
+            })
+            .buffered(self.max_concurrency)
+            .collect()
+            .await;
+        join_pages(results)
+    }
+
+    /// Recognizes the pages of one document, such as the frames of a multi-page TIFF, into
+    /// one result with a page per image, numbered from 1.
+    ///
+    /// Pages are recognized in parallel, up to [`OcrEngine::with_max_concurrency`] at a time;
+    /// a limit of 1 recognizes them one after another.
+    pub async fn recognize_pages(&self, images: &[DynamicImage]) -> Result<OcrResult> {
+        let results = stream::iter(images)
+            .map(|image| self.recognize_page(image))
+            .buffered(self.max_concurrency)
+            .collect()
+            .await;
+        join_pages(results)
+    }
+
+    /// Recognizes one page of a document, returning the result too for its provider and
+    /// confidence reporting.
+    async fn recognize_page(&self, image: &DynamicImage) -> Result<(Page, Option<OcrResult>)> {
+        let mut result = self.recognize_image(image).await?;
+        let page = result
+            .pages
+            .pop()
+            .unwrap_or_else(|| Page::new(image.width(), image.height(), Vec::new()));
+        Ok((page, Some(result)))
     }
 
-    pub async fn recognize_batch(
+    /// Recognizes every source with up to [`OcrEngine::with_max_concurrency`] items in flight.
+    ///
+    /// Returns one result per source, in input order; a failing item does not affect the
+    /// others.
+    pub async fn recognize_batch<I>(&self, sources: I) -> Vec<Result<OcrResult>>
+    where
+        I: IntoIterator,
+        I::Item: Into<ImageSource>,
+    {
+        stream::iter(sources)
+            .map(|source| self.recognize_source(source.into()))
+            .buffered(self.max_concurrency)
+            .collect()
+            .await
+    }
+
+    /// Recognizes only the given regions of `image`, with up to
+    /// [`OcrEngine::with_max_concurrency`] regions in flight.
+    ///
+    /// Returns one result per region, in input order. Boxes are in the coordinates of the
+    /// whole `image`. Regions are clipped to the image; one lying entirely outside it fails
+    /// with [`OcrError::InvalidImage`].
+    pub async fn recognize_regions(
         &self,
-        paths: Vec<&str>,
-    ) -> Result<Vec<(String, String, Option<f64>)>> {
-        let mut results = Vec::with_capacity(paths.len());
-        for path in paths {
-            results.push(self.recognize_file(path).await?);
+        image: &DynamicImage,
+        regions: &[Rect],
+    ) -> Vec<Result<OcrResult>> {
+        stream::iter(regions)
+            .map(|region| self.recognize_region(image, *region))
+            .buffered(self.max_concurrency)
+            .collect()
+            .await
+    }
+
+    async fn recognize_region(&self, image: &DynamicImage, region: Rect) -> Result<OcrResult> {
+        let (width, height) = (image.width(), image.height());
+        let region = region.clamp_to(width, height).ok_or_else(|| {
+            OcrError::InvalidImage(format!(
+                "region {:?} lies outside the {}x{} image",
+                region, width, height
+            ))
+        })?;
+
+        let crop = image.crop_imm(region.x, region.y, region.width, region.height);
+        let mut result = self.recognize_image(&crop).await?;
+        let (dx, dy) = (region.x as f64, region.y as f64);
+        result.map_boxes(|b| BoundingBox::new(b.x + dx, b.y + dy, b.width, b.height));
+        for page in &mut result.pages {
+            page.width = width;
+            page.height = height;
         }
-        Ok(results)
+        Ok(result)
+    }
+
+    /// Recognizes sources as they arrive and yields `(index, result)` in completion order,
+    /// where `index` is the source's position in the input stream.
+    ///
+    /// At most [`OcrEngine::with_max_concurrency`] sources are pulled from `sources` and in
+    /// flight at once, so a slow consumer slows down reading the input. Dropping the returned
+    /// stream cancels pending work, including running Tesseract processes, which are killed.
+    pub fn recognize_stream<'a, S>(
+        &'a self,
+        sources: S,
+    ) -> impl Stream<Item = (usize, Result<OcrResult>)> + 'a
+    where
+        S: Stream + 'a,
+        S::Item: Into<ImageSource>,
+    {
+        sources
+            .enumerate()
+            .map(move |(index, source)| async move {
+                (index, self.recognize_source(source.into()).await)
+            })
+            .buffer_unordered(self.max_concurrency)
     }
 }
 
+pub mod alto;
 #[cfg(target_os = "macos")]
 pub mod apple;
+pub mod backend;
+mod charset;
 pub mod custom_ocr;
+pub mod error;
+mod hocr;
 pub mod language;
 #[cfg(target_os = "windows")]
 pub mod microsoft;
+pub mod pdf;
+pub mod preprocess;
+pub mod region;
+pub mod result;
+pub mod source;
 pub mod tesseract;
+pub mod tiling;
+mod xml;
 
 #[cfg(target_os = "macos")]
-pub use apple::perform_ocr_apple;
+pub use apple::{perform_ocr_apple, AppleBackend};
 pub use custom_ocr::perform_ocr_custom;
 pub use language::*;
 #[cfg(target_os = "windows")]
-pub use microsoft::perform_ocr_windows;
-pub use tesseract::perform_ocr_tesseract;
+pub use microsoft::{perform_ocr_windows, WindowsBackend};
+pub use tesseract::{
+    detect_orientation, installed_languages, installed_languages_in, perform_ocr_tesseract,
+    TesseractBackend, TesseractOptions,
+};
+
+/// Joins the pages of a document, in order, numbering them from 1. Pages read from a text
+/// layer come without a recognition result.
+fn join_pages(pages: Vec<Result<(Page, Option<OcrResult>)>>) -> Result<OcrResult> {
+    let mut document = OcrResult::new(Vec::with_capacity(pages.len()));
+    for (index, page) in pages.into_iter().enumerate() {
+        let (mut page, recognized) = page?;
+        page.number = index as u32 + 1;
+        match recognized {
+            Some(recognized) => {
+                document.confidence_reported &= recognized.confidence_reported;
+                document.provider = document.provider.or(recognized.provider);
+            }
+            // Text-layer words have no confidence of their own
+            None if page.words().next().is_some() => document.confidence_reported = false,
+            None => {}
+        }
+        document.pages.push(page);
+    }
+    Ok(document)
+}
+
+fn default_concurrency() -> usize {
+    std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+}
@@ -1,8 +1,69 @@
+use async_trait::async_trait;
 use image::{DynamicImage, GenericImageView};
-use anyhow::Result;
+
+use crate::backend::{run_blocking, Capabilities, OcrBackend, ProviderStatus};
+use crate::error::{OcrError, Result};
+use crate::result::{BoundingBox, Line, OcrResult, Word};
+use crate::{Language, OcrOptions};
+
+/// Backend using `Windows.Media.Ocr` with the user profile languages.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WindowsBackend;
+
+#[async_trait]
+impl OcrBackend for WindowsBackend {
+    fn name(&self) -> &str {
+        "windows"
+    }
+
+    fn supported_languages(&self) -> Vec<Language> {
+        use windows::Media::Ocr::OcrEngine as WindowsOcrEngine;
+
+        let Ok(available) = WindowsOcrEngine::AvailableRecognizerLanguages() else {
+            return Vec::new();
+        };
+        available
+            .into_iter()
+            .filter_map(|lang| lang.LanguageTag().ok())
+            .filter_map(|tag| {
+                let tag = tag.to_string();
+                let code = tag.split('-').next().unwrap_or_default().to_string();
+                Language::all()
+                    .iter()
+                    .copied()
+                    .find(|lang| lang.as_lang_code() == code)
+            })
+            .collect()
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            word_boxes: true,
+            confidence: false,
+            blocking: true,
+        }
+    }
+
+    async fn probe(&self) -> ProviderStatus {
+        let languages = self.supported_languages();
+        if languages.is_empty() {
+            return ProviderStatus::unavailable(self.name(), "no OCR language pack installed");
+        }
+        ProviderStatus::available(self.name(), languages)
+    }
+
+    async fn recognize(&self, image: &DynamicImage, _options: &OcrOptions) -> Result<OcrResult> {
+        // The WinRT calls block on `.get()`, so drive them from the blocking pool
+        let image = image.clone();
+        run_blocking(move || {
+            tokio::runtime::Handle::current().block_on(perform_ocr_windows(&image))
+        })
+        .await
+    }
+}
 
 #[cfg(target_os = "windows")]
-pub async fn perform_ocr_windows(image: &DynamicImage) -> Result<(String, String, Option<f64>)> {
+pub async fn perform_ocr_windows(image: &DynamicImage) -> Result<OcrResult> {
     use std::io::Cursor;
     use windows::{
         Graphics::Imaging::BitmapDecoder,
@@ -14,13 +75,13 @@ pub async fn perform_ocr_windows(image: &DynamicImage) -> Result<(String, String
     let (width, height) = image.dimensions();
     if width == 0 || height == 0 {
         // Return an empty result instead of panicking
-        return Ok(("".to_string(), "[]".to_string(), None));
+        return Ok(OcrResult::from_lines(width, height, Vec::new()));
     }
 
     let mut buffer = Vec::new();
     image
         .write_to(&mut Cursor::new(&mut buffer), image::ImageFormat::Png)
-        .map_err(|e| anyhow::anyhow!("Failed to write image to buffer: {}", e))?;
+        .map_err(|e| OcrError::InvalidImage(format!("Failed to write image to buffer: {}", e)))?;
 
     let stream = InMemoryRandomAccessStream::new()?;
     let writer = DataWriter::CreateDataWriter(&stream)?;
@@ -29,21 +90,39 @@ pub async fn perform_ocr_windows(image: &DynamicImage) -> Result<(String, String
     writer.FlushAsync()?.get()?;
     stream.Seek(0)?;
 
-    let decoder = BitmapDecoder::CreateWithIdAsync(BitmapDecoder::PngDecoderId()?, &stream)?
-        .get()?;
+    let decoder =
+        BitmapDecoder::CreateWithIdAsync(BitmapDecoder::PngDecoderId()?, &stream)?.get()?;
 
     let bitmap = decoder.GetSoftwareBitmapAsync()?.get()?;
 
-    let engine = WindowsOcrEngine::TryCreateFromUserProfileLanguages()?;
+    let engine = WindowsOcrEngine::TryCreateFromUserProfileLanguages().map_err(|_| {
+        OcrError::LanguageNotInstalled(
+            "no OCR language pack installed for the user profile languages".to_string(),
+        )
+    })?;
     let result = engine.RecognizeAsync(&bitmap)?.get()?;
 
-    let text = result.Text()?.to_string();
-
-    let json_output = serde_json::json!([{
-        "text": text,
-        "confidence": "1.0" // Windows OCR doesn't provide confidence scores
-    }])
-    .to_string();
+    let mut lines = Vec::new();
+    for line in result.Lines()? {
+        let mut words = Vec::new();
+        for word in line.Words()? {
+            // Windows reports pixel coordinates with a top-left origin already
+            let rect = word.BoundingRect()?;
+            let bbox = BoundingBox::new(
+                rect.X as f64,
+                rect.Y as f64,
+                rect.Width as f64,
+                rect.Height as f64,
+            );
+            // Windows OCR doesn't provide confidence scores
+            words.push(Word::new(word.Text()?.to_string(), bbox, 1.0));
+        }
+        if !words.is_empty() {
+            lines.push(Line::from_words(words));
+        }
+    }
 
-    Ok((text, json_output, Some(1.0)))
+    let mut ocr_result = OcrResult::from_lines(width, height, lines);
+    ocr_result.confidence_reported = false;
+    Ok(ocr_result)
 }
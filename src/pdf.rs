@@ -0,0 +1,1385 @@
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::Path;
+
+use fax::decoder::{decode_g3, decode_g4, pels};
+use fax::Color;
+use image::{DynamicImage, ImageFormat, Rgb, RgbImage};
+use lopdf::content::{Content, Operation};
+use lopdf::{dictionary, Dictionary, Document, Encoding, Object, ObjectId, Stream};
+use tracing::warn;
+
+use crate::error::{OcrError, Result};
+use crate::result::{Block, BoundingBox, Line, OcrResult, Page, Paragraph, Word};
+
+/// Font name used for the text layer. Its only glyph is blank: the text is never drawn, it is
+/// only there to be selected and searched.
+const FONT: &str = "GlyphLessFont";
+
+/// Width of every glyph, in thousandths of the font size.
+const GLYPH_WIDTH: f64 = 500.0;
+
+/// Maps every 2-byte code to the same UTF-16 code unit, so viewers can extract the text.
+const TO_UNICODE: &str = "/CIDInit /ProcSet findresource begin
+12 dict begin
+begincmap
+/CIDSystemInfo << /Registry (Adobe) /Ordering (UCS) /Supplement 0 >> def
+/CMapName /Adobe-Identity-UCS def
+/CMapType 2 def
+1 begincodespacerange
+<0000> <FFFF>
+endcodespacerange
+1 beginbfrange
+<0000> <FFFF> <0000>
+endbfrange
+endcmap
+CMapName currentdict /CMap defineresource pop
+end
+end
+";
+
+/// Writes searchable PDFs: each page shows a recognized image, with its words laid over it as
+/// invisible text at their bounding boxes.
+///
+/// ```no_run
+/// # async fn run(engine: uni_ocr::OcrEngine, scans: Vec<image::DynamicImage>) -> uni_ocr::Result<()> {
+/// let mut pdf = uni_ocr::PdfWriter::new().dpi(300.0);
+/// for scan in &scans {
+///     let result = engine.recognize_image(scan).await?;
+///     pdf.add_page(scan, &result.pages[0])?;
+/// }
+/// pdf.save("scans.pdf")?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct PdfWriter {
+    document: Document,
+    pages_id: ObjectId,
+    font_id: ObjectId,
+    pages: Vec<Object>,
+    dpi: f64,
+}
+
+impl Default for PdfWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PdfWriter {
+    pub fn new() -> Self {
+        let mut document = Document::with_version("1.5");
+        let pages_id = document.new_object_id();
+        let font_id = document.new_object_id();
+        Self {
+            document,
+            pages_id,
+            font_id,
+            pages: Vec::new(),
+            dpi: 300.0,
+        }
+    }
+
+    /// Resolution of the images, which sets the page size. Defaults to 300.
+    pub fn dpi(mut self, dpi: f64) -> Self {
+        self.dpi = dpi;
+        self
+    }
+
+    /// Adds a page showing `image`, with the words of `page`, the page recognized from
+    /// `image`, as its text layer. Words without a box are left out.
+    pub fn add_page(&mut self, image: &DynamicImage, page: &Page) -> Result<&mut Self> {
+        let (width, height) = (image.width(), image.height());
+        let points = 72.0 / self.dpi;
+        let (page_width, page_height) = (width as f64 * points, height as f64 * points);
+
+        let (color_space, pixels) = if image.color().has_color() {
+            ("DeviceRGB", image.to_rgb8().into_raw())
+        } else {
+            ("DeviceGray", image.to_luma8().into_raw())
+        };
+        let mut image_stream = Stream::new(
+            dictionary! {
+                "Type" => "XObject",
+                "Subtype" => "Image",
+                "Width" => width,
+                "Height" => height,
+                "ColorSpace" => color_space,
+                "BitsPerComponent" => 8,
+            },
+            pixels,
+        );
+        image_stream.compress()?;
+        let image_id = self.document.add_object(image_stream);
+
+        // Boxes are in the coordinates of the recognized page, normally the image itself
+        let (scale_x, scale_y) = if page.width > 0 && page.height > 0 {
+            (
+                width as f64 / page.width as f64 * points,
+                height as f64 / page.height as f64 * points,
+            )
+        } else {
+            (points, points)
+        };
+        let mut content = format!(
+            "q\n{:.2} 0 0 {:.2} 0 0 cm\n/Im1 Do\nQ\n",
+            page_width, page_height
+        );
+        let lines = page
+            .blocks
+            .iter()
+            .flat_map(|block| &block.paragraphs)
+            .flat_map(|paragraph| &paragraph.lines);
+        for line in lines {
+            write_line(&mut content, line, scale_x, scale_y, page_height);
+        }
+        let mut content_stream = Stream::new(dictionary! {}, content.into_bytes());
+        content_stream.compress()?;
+        let content_id = self.document.add_object(content_stream);
+
+        let page_id = self.document.add_object(dictionary! {
+            "Type" => "Page",
+            "Parent" => self.pages_id,
+            "MediaBox" => vec![0.into(), 0.into(), page_width.into(), page_height.into()],
+            "Resources" => dictionary! {
+                "Font" => dictionary! { "F1" => self.font_id },
+                "XObject" => dictionary! { "Im1" => image_id },
+            },
+            "Contents" => content_id,
+        });
+        self.pages.push(page_id.into());
+        Ok(self)
+    }
+
+    /// Number of pages added so far.
+    pub fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+
+    /// The finished PDF.
+    pub fn to_bytes(mut self) -> Result<Vec<u8>> {
+        let pages = dictionary! {
+            "Type" => "Pages",
+            "Kids" => self.pages.clone(),
+            "Count" => self.pages.len() as i64,
+        };
+        self.document.objects.insert(self.pages_id, pages.into());
+        add_font(&mut self.document, self.font_id)?;
+        let catalog_id = self.document.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => self.pages_id,
+        });
+        self.document.trailer.set("Root", catalog_id);
+
+        let mut bytes = Vec::new();
+        self.document.save_to(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// Writes the finished PDF to `path`.
+    pub fn save(self, path: impl AsRef<Path>) -> Result<()> {
+        std::fs::write(path, self.to_bytes()?)?;
+        Ok(())
+    }
+}
+
+impl OcrResult {
+    /// A searchable PDF with a page per result page, showing `images`, the images the pages
+    /// were recognized from, in order.
+    pub fn to_pdf<'a>(
+        &self,
+        images: impl IntoIterator<Item = &'a DynamicImage>,
+    ) -> Result<Vec<u8>> {
+        let mut pdf = PdfWriter::new();
+        let mut images = images.into_iter();
+        for page in &self.pages {
+            let image = images.next().ok_or_else(|| {
+                OcrError::InvalidImage(format!(
+                    "{} pages but only {} images",
+                    self.pages.len(),
+                    pdf.page_count()
+                ))
+            })?;
+            pdf.add_page(image, page)?;
+        }
+        if images.next().is_some() {
+            return Err(OcrError::InvalidImage(format!(
+                "more images than the {} pages",
+                self.pages.len()
+            )));
+        }
+        pdf.to_bytes()
+    }
+}
+
+/// Adds the text layer font: a Type0 font with 2-byte codes holding UTF-16 code units, all
+/// drawn with the blank glyph of an embedded TrueType font.
+fn add_font(document: &mut Document, font_id: ObjectId) -> Result<()> {
+    let font = glyphless_font();
+    let mut font_file = Stream::new(dictionary! { "Length1" => font.len() as i64 }, font);
+    font_file.compress()?;
+    let font_file_id = document.add_object(font_file);
+    // Every CID is glyph 1
+    let mut cid_to_gid = Stream::new(dictionary! {}, [0u8, 1].repeat(0x10000));
+    cid_to_gid.compress()?;
+    let cid_to_gid_id = document.add_object(cid_to_gid);
+
+    let descriptor_id = document.add_object(dictionary! {
+        "Type" => "FontDescriptor",
+        "FontName" => FONT,
+        "Flags" => 5,
+        "FontBBox" => vec![0.into(), 0.into(), (GLYPH_WIDTH as i64).into(), 1000.into()],
+        "ItalicAngle" => 0,
+        "Ascent" => 1000,
+        "Descent" => 0,
+        "CapHeight" => 1000,
+        "StemV" => 80,
+        "FontFile2" => font_file_id,
+    });
+    let cid_font_id = document.add_object(dictionary! {
+        "Type" => "Font",
+        "Subtype" => "CIDFontType2",
+        "BaseFont" => FONT,
+        "CIDSystemInfo" => dictionary! {
+            "Registry" => Object::string_literal("Adobe"),
+            "Ordering" => Object::string_literal("Identity"),
+            "Supplement" => 0,
+        },
+        "FontDescriptor" => descriptor_id,
+        "DW" => GLYPH_WIDTH as i64,
+        "CIDToGIDMap" => cid_to_gid_id,
+    });
+    let to_unicode_id =
+        document.add_object(Stream::new(dictionary! {}, TO_UNICODE.as_bytes().to_vec()));
+    let font = dictionary! {
+        "Type" => "Font",
+        "Subtype" => "Type0",
+        "BaseFont" => FONT,
+        "Encoding" => "Identity-H",
+        "DescendantFonts" => vec![cid_font_id.into()],
+        "ToUnicode" => to_unicode_id,
+    };
+    document.objects.insert(font_id, font.into());
+    Ok(())
+}
+
+/// A TrueType font with a blank `.notdef` and one blank glyph, both `GLYPH_WIDTH` wide on a
+/// 1000 unit em.
+fn glyphless_font() -> Vec<u8> {
+    fn u16s(values: &[u16]) -> Vec<u8> {
+        values.iter().flat_map(|v| v.to_be_bytes()).collect()
+    }
+    let width = GLYPH_WIDTH as u16;
+
+    let mut head = u16s(&[1, 0, 1, 0, 0, 0, 0x5F0F, 0x3CF5, 0b1011, 1000]);
+    head.extend([0; 16]); // created and modified
+    head.extend(u16s(&[0, 0, width, 1000, 0, 8, 2, 0, 0])); // bbox .. glyphDataFormat
+    let hhea = u16s(&[
+        1, 0, 1000, 0, 0, width, 0, 0, width, 1, 0, 0, 0, 0, 0, 0, 0, 2,
+    ]);
+    let maxp = u16s(&[1, 0, 2, 0, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 0]);
+    let hmtx = u16s(&[width, 0, width, 0]);
+    let loca = u16s(&[0, 0, 0]);
+    // A format 4 subtable with only the closing segment: no character maps to a glyph
+    let cmap = u16s(&[
+        0, 1, 3, 1, 0, 12, 4, 24, 0, 2, 2, 0, 0, 0xFFFF, 0, 0xFFFF, 1, 0,
+    ]);
+    let post = u16s(&[
+        3,
+        0,
+        0,
+        0,
+        (-100i16) as u16,
+        50,
+        0,
+        1,
+        0,
+        0,
+        0,
+        0,
+        0,
+        0,
+        0,
+        0,
+    ]);
+    let name_string: Vec<u16> = FONT.encode_utf16().collect();
+    let name_length = 2 * name_string.len() as u16;
+    let mut name = u16s(&[0, 3, 6 + 3 * 12]);
+    for name_id in [1, 4, 6] {
+        name.extend(u16s(&[3, 1, 0x409, name_id, name_length, 0]));
+    }
+    name.extend(u16s(&name_string));
+
+    // Sorted by tag, as the table directory requires
+    let tables: [(&[u8; 4], Vec<u8>); 9] = [
+        (b"cmap", cmap),
+        (b"glyf", Vec::new()),
+        (b"head", head),
+        (b"hhea", hhea),
+        (b"hmtx", hmtx),
+        (b"loca", loca),
+        (b"maxp", maxp),
+        (b"name", name),
+        (b"post", post),
+    ];
+    let checksum = |data: &[u8]| {
+        data.chunks(4).fold(0u32, |sum, chunk| {
+            let mut word = [0; 4];
+            word[..chunk.len()].copy_from_slice(chunk);
+            sum.wrapping_add(u32::from_be_bytes(word))
+        })
+    };
+
+    let count = tables.len() as u16;
+    let search_range = 16 * (1 << count.ilog2());
+    let mut font = u16s(&[
+        1,
+        0,
+        count,
+        search_range,
+        count.ilog2() as u16,
+        16 * count - search_range,
+    ]);
+    let mut offset = 12 + 16 * tables.len();
+    let mut head_offset = 0;
+    for (tag, data) in &tables {
+        font.extend(*tag);
+        font.extend(checksum(data).to_be_bytes());
+        font.extend((offset as u32).to_be_bytes());
+        font.extend((data.len() as u32).to_be_bytes());
+        if *tag == b"head" {
+            head_offset = offset;
+        }
+        offset += data.len().next_multiple_of(4);
+    }
+    for (_, data) in &tables {
+        font.extend(data);
+        font.resize(font.len().next_multiple_of(4), 0);
+    }
+    let adjustment = 0xB1B0_AFBAu32.wrapping_sub(checksum(&font));
+    font[head_offset + 8..head_offset + 12].copy_from_slice(&adjustment.to_be_bytes());
+    font
+}
+
+/// One text object per line, each word stretched over its box in invisible render mode.
+fn write_line(content: &mut String, line: &Line, scale_x: f64, scale_y: f64, page_height: f64) {
+    let words: Vec<_> = line.words.iter().filter(|w| !w.bbox.is_empty()).collect();
+    if words.is_empty() {
+        return;
+    }
+    content.push_str("BT\n3 Tr\n");
+    for (i, word) in words.iter().enumerate() {
+        let units: Vec<u16> = word.text.encode_utf16().collect();
+        if units.is_empty() {
+            continue;
+        }
+        let size = word.bbox.height * scale_y;
+        let width = word.bbox.width * scale_x;
+        let stretch = 100.0 * width / (units.len() as f64 * size * GLYPH_WIDTH / 1000.0);
+        let (x, y) = (
+            word.bbox.x * scale_x,
+            page_height - word.bbox.bottom() * scale_y,
+        );
+
+        // A trailing space keeps words apart when the text is copied
+        let mut hex = String::with_capacity(4 * units.len() + 4);
+        for unit in units.iter().chain((i + 1 < words.len()).then_some(&0x20)) {
+            let _ = write!(hex, "{:04X}", unit);
+        }
+        let _ = write!(
+            content,
+            "/F1 {:.2} Tf\n{:.2} Tz\n1 0 0 1 {:.2} {:.2} Tm\n<{}> Tj\n",
+            size, stretch, x, y, hex
+        );
+    }
+    content.push_str("ET\n");
+}
+
+/// Whether `path` names a PDF, by its extension.
+pub(crate) fn is_pdf(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("pdf"))
+}
+
+/// One page of a PDF input: its text layer when that covers the page's images, otherwise the
+/// images rendered for recognition, with whatever text the page also shows.
+pub(crate) enum PdfPage {
+    Text(Page),
+    Image {
+        image: DynamicImage,
+        text: Vec<Line>,
+    },
+}
+
+/// Adds a page's text layer to the words recognized on its scan, as one more block. Recognized
+/// words centered on a text-layer word are dropped as duplicates.
+pub(crate) fn merge_text(page: &mut Page, text: Vec<Line>) {
+    if text.is_empty() {
+        return;
+    }
+    let layer: Vec<BoundingBox> = text.iter().flat_map(|l| &l.words).map(|w| w.bbox).collect();
+    page.filter_words(|word| {
+        let duplicate = layer.iter().any(|bbox| contains_center(bbox, &word.bbox));
+        (!duplicate).then_some(word)
+    });
+    page.blocks
+        .push(Block::from_paragraphs(vec![Paragraph::from_lines(text)]));
+}
+
+fn contains_center(outer: &BoundingBox, inner: &BoundingBox) -> bool {
+    let (x, y) = (inner.x + inner.width / 2.0, inner.y + inner.height / 2.0);
+    (outer.x..=outer.right()).contains(&x) && (outer.y..=outer.bottom()).contains(&y)
+}
+
+/// Whether the text over an image stands for what it shows: OCR tools lay invisible text over
+/// their scans, while visible text such as a stamped page number covers only part of it.
+fn covers(glyphs: &[Glyph], image: &BoundingBox) -> bool {
+    let mut extent = BoundingBox::default();
+    for glyph in glyphs {
+        if glyph.text.trim().is_empty() || !contains_center(image, &glyph.bbox) {
+            continue;
+        }
+        if glyph.invisible {
+            return true;
+        }
+        extent = extent.union(&glyph.bbox);
+    }
+    extent.width * extent.height >= image.width * image.height / 2.0
+}
+
+/// Reads the pages of a PDF input.
+///
+/// Only what scanners and OCR tools produce is rendered: image XObjects, directly or inside
+/// forms, on a white page, turned by the page's `/Rotate`. Vector graphics and text drawn as
+/// outlines are not, and JBIG2, JPEG 2000 and CCITT Group 3 2D images are skipped with a
+/// warning.
+pub(crate) struct PdfReader {
+    document: Document,
+    pages: Vec<ObjectId>,
+}
+
+impl PdfReader {
+    pub fn open(path: &Path) -> Result<Self> {
+        let document = Document::load(path).map_err(invalid_pdf)?;
+        let pages = document.get_pages().into_values().collect();
+        Ok(Self { document, pages })
+    }
+
+    pub fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+
+    /// Page `index`, counted from 0, with positions in pixels at `dpi`.
+    pub fn page(&self, index: usize, dpi: u32) -> Result<PdfPage> {
+        let page_id = self.pages[index];
+        let [x0, y0, x1, y1] = self.media_box(page_id)?;
+        let scale = dpi as f64 / 72.0;
+        let width = ((x1 - x0) * scale).round().max(1.0);
+        let height = ((y1 - y0) * scale).round().max(1.0);
+        if width * height > MAX_PAGE_PIXELS {
+            return Err(OcrError::InvalidImage(format!(
+                "PDF page {} would render at {}x{} pixels; lower the DPI",
+                index + 1,
+                width,
+                height
+            )));
+        }
+        let (width, height) = (width as u32, height as u32);
+
+        let content = self
+            .document
+            .get_page_content(page_id)
+            .and_then(|data| Content::decode(&data))
+            .map_err(invalid_pdf)?;
+        let (direct, inherited) = self
+            .document
+            .get_page_resources(page_id)
+            .map_err(invalid_pdf)?;
+        let resources: Vec<&Dictionary> = direct
+            .into_iter()
+            .chain(
+                inherited
+                    .into_iter()
+                    .filter_map(|id| self.document.get_dictionary(id).ok()),
+            )
+            .collect();
+
+        // User space to pixels, with the origin at the top left, then turned clockwise the
+        // way viewers show the page
+        let upright = Matrix([scale, 0.0, 0.0, -scale, -x0 * scale, y1 * scale]);
+        let (w, h) = (width as f64, height as f64);
+        let (device, width, height) = match self.quarter_turns(page_id) {
+            1 => (
+                upright.then(Matrix([0.0, 1.0, -1.0, 0.0, h, 0.0])),
+                height,
+                width,
+            ),
+            2 => (
+                upright.then(Matrix([-1.0, 0.0, 0.0, -1.0, w, h])),
+                width,
+                height,
+            ),
+            3 => (
+                upright.then(Matrix([0.0, -1.0, 1.0, 0.0, 0.0, w])),
+                height,
+                width,
+            ),
+            _ => (upright, width, height),
+        };
+        let mut interpreter = Interpreter::new(&self.document);
+        interpreter.run(&content.operations, &resources, device, 0);
+        let lines = glyph_lines(&interpreter.glyphs);
+
+        // Images the text layer stands for are not recognized again
+        let mut canvas = None;
+        for (stream, matrix) in interpreter.images {
+            let (left, top, right, bottom) = matrix.bounds(0.0, 0.0, 1.0, 1.0);
+            let bounds = BoundingBox::new(left, top, right - left, bottom - top);
+            if covers(&interpreter.glyphs, &bounds) {
+                continue;
+            }
+            match decode_image(&self.document, stream) {
+                Ok(image) => draw_image(
+                    canvas.get_or_insert_with(|| {
+                        RgbImage::from_pixel(width, height, Rgb([255, 255, 255]))
+                    }),
+                    &image,
+                    matrix,
+                ),
+                Err(err) => warn!("skipping an image on PDF page {}: {}", index + 1, err),
+            }
+        }
+        Ok(match canvas {
+            Some(canvas) => PdfPage::Image {
+                image: DynamicImage::ImageRgb8(canvas),
+                text: lines,
+            },
+            None => PdfPage::Text(OcrResult::from_lines(width, height, lines).pages.remove(0)),
+        })
+    }
+
+    /// `[x0, y0, x1, y1]` of the page.
+    fn media_box(&self, page_id: ObjectId) -> Result<[f64; 4]> {
+        if let Some(media_box) = self.inherited(page_id, b"MediaBox") {
+            let values: Vec<f64> = media_box
+                .as_array()
+                .map_err(invalid_pdf)?
+                .iter()
+                .filter_map(number)
+                .collect();
+            if let [a, b, c, d] = values[..] {
+                if !values.iter().all(|value| value.is_finite()) {
+                    return Err(OcrError::InvalidImage(format!(
+                        "invalid PDF MediaBox {:?}",
+                        values
+                    )));
+                }
+                return Ok([a.min(c), b.min(d), a.max(c), b.max(d)]);
+            }
+        }
+        // US Letter, the default of most PDF producers
+        Ok([0.0, 0.0, 612.0, 792.0])
+    }
+
+    /// Clockwise quarter turns viewers apply to the page, from its `/Rotate`.
+    fn quarter_turns(&self, page_id: ObjectId) -> i64 {
+        let degrees = self
+            .inherited(page_id, b"Rotate")
+            .and_then(number)
+            .unwrap_or(0.0);
+        ((degrees / 90.0).round() as i64).rem_euclid(4)
+    }
+
+    /// A page attribute, which may be inherited from the page tree.
+    fn inherited(&self, page_id: ObjectId, key: &[u8]) -> Option<&Object> {
+        let mut node = self.document.get_dictionary(page_id).ok()?;
+        for _ in 0..MAX_DEPTH {
+            if let Ok(value) = node.get(key) {
+                return Some(resolve(&self.document, value));
+            }
+            node = node
+                .get(b"Parent")
+                .and_then(Object::as_reference)
+                .and_then(|id| self.document.get_dictionary(id))
+                .ok()?;
+        }
+        None
+    }
+}
+
+fn invalid_pdf(err: lopdf::Error) -> OcrError {
+    OcrError::InvalidImage(format!("unreadable PDF: {}", err))
+}
+
+/// Limit on nested forms and page tree levels.
+const MAX_DEPTH: usize = 16;
+
+/// Largest page [`PdfReader::page`] renders, about 800 MB as RGB. A0 at 300 DPI fits.
+const MAX_PAGE_PIXELS: f64 = (1u64 << 28) as f64;
+
+/// Affine transform `[a b c d e f]`, as in PDF content streams.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Matrix([f64; 6]);
+
+impl Matrix {
+    const IDENTITY: Matrix = Matrix([1.0, 0.0, 0.0, 1.0, 0.0, 0.0]);
+
+    fn translate(x: f64, y: f64) -> Self {
+        Matrix([1.0, 0.0, 0.0, 1.0, x, y])
+    }
+
+    fn from_operands(operands: &[Object]) -> Option<Self> {
+        let values: Vec<f64> = operands.iter().filter_map(number).collect();
+        let values: [f64; 6] = values.try_into().ok()?;
+        Some(Matrix(values))
+    }
+
+    /// `self` followed by `then`.
+    fn then(self, then: Matrix) -> Matrix {
+        let [a, b, c, d, e, f] = self.0;
+        let [a2, b2, c2, d2, e2, f2] = then.0;
+        Matrix([
+            a * a2 + b * c2,
+            a * b2 + b * d2,
+            c * a2 + d * c2,
+            c * b2 + d * d2,
+            e * a2 + f * c2 + e2,
+            e * b2 + f * d2 + f2,
+        ])
+    }
+
+    fn apply(&self, x: f64, y: f64) -> (f64, f64) {
+        let [a, b, c, d, e, f] = self.0;
+        (a * x + c * y + e, b * x + d * y + f)
+    }
+
+    fn invert(&self) -> Option<Matrix> {
+        let [a, b, c, d, e, f] = self.0;
+        let det = a * d - b * c;
+        if det.abs() < 1e-12 {
+            return None;
+        }
+        Some(Matrix([
+            d / det,
+            -b / det,
+            -c / det,
+            a / det,
+            (c * f - d * e) / det,
+            (b * e - a * f) / det,
+        ]))
+    }
+
+    /// Bounding box, in `(x0, y0, x1, y1)`, of the rectangle `(x0, y0)`–`(x1, y1)`.
+    fn bounds(&self, x0: f64, y0: f64, x1: f64, y1: f64) -> (f64, f64, f64, f64) {
+        let corners = [
+            self.apply(x0, y0),
+            self.apply(x1, y0),
+            self.apply(x0, y1),
+            self.apply(x1, y1),
+        ];
+        corners.iter().fold(
+            (f64::MAX, f64::MAX, f64::MIN, f64::MIN),
+            |(l, t, r, b), &(x, y)| (l.min(x), t.min(y), r.max(x), b.max(y)),
+        )
+    }
+}
+
+fn number(object: &Object) -> Option<f64> {
+    object.as_float().ok().map(f64::from)
+}
+
+fn resolve<'a>(document: &'a Document, object: &'a Object) -> &'a Object {
+    document
+        .dereference(object)
+        .map(|(_, object)| object)
+        .unwrap_or(object)
+}
+
+/// What the text layer needs from a font: decoding and glyph metrics.
+struct Font<'a> {
+    encoding: Encoding<'a>,
+    /// Type0 fonts use 2-byte codes, simple fonts 1-byte codes.
+    two_byte: bool,
+    widths: HashMap<u32, f64>,
+    default_width: f64,
+    ascent: f64,
+    descent: f64,
+}
+
+impl<'a> Font<'a> {
+    fn load(document: &'a Document, font: &'a Dictionary) -> Option<Self> {
+        let encoding = font.get_font_encoding(document).ok()?;
+        let dict = |object: &'a Object| resolve(document, object).as_dict().ok();
+        let two_byte = font.get(b"Subtype").and_then(Object::as_name).ok() == Some(b"Type0");
+        let mut widths = HashMap::new();
+
+        let (metrics, default_width) = if two_byte {
+            let descendant = font
+                .get(b"DescendantFonts")
+                .map(|fonts| resolve(document, fonts))
+                .and_then(Object::as_array)
+                .ok()
+                .and_then(|fonts| fonts.first())
+                .and_then(dict)?;
+            // W is a list of `first [w1 w2 ...]` and `first last w` entries
+            if let Ok(w) = descendant.get(b"W").map(|w| resolve(document, w)) {
+                let w = w.as_array().map(Vec::as_slice).unwrap_or_default();
+                let mut i = 0;
+                while i + 1 < w.len() {
+                    let first = number(&w[i]).unwrap_or(0.0) as u32;
+                    if let Ok(list) = resolve(document, &w[i + 1]).as_array() {
+                        for (offset, width) in list.iter().enumerate() {
+                            if let Some(width) = number(width) {
+                                widths.insert(first + offset as u32, width);
+                            }
+                        }
+                        i += 2;
+                    } else if let (Some(last), Some(width)) =
+                        (number(&w[i + 1]), w.get(i + 2).and_then(number))
+                    {
+                        for code in first..=last as u32 {
+                            widths.insert(code, width);
+                        }
+                        i += 3;
+                    } else {
+                        break;
+                    }
+                }
+            }
+            let default_width = descendant
+                .get(b"DW")
+                .ok()
+                .and_then(number)
+                .unwrap_or(1000.0);
+            (descendant, default_width)
+        } else {
+            let first = font.get(b"FirstChar").ok().and_then(number).unwrap_or(0.0) as u32;
+            if let Ok(list) = font
+                .get(b"Widths")
+                .map(|w| resolve(document, w))
+                .and_then(Object::as_array)
+            {
+                for (offset, width) in list.iter().enumerate() {
+                    if let Some(width) = number(resolve(document, width)) {
+                        widths.insert(first + offset as u32, width);
+                    }
+                }
+            }
+            // Fonts without widths, such as the standard 14, get an average width
+            (font, 500.0)
+        };
+
+        let descriptor = metrics.get(b"FontDescriptor").ok().and_then(dict);
+        let metric = |key: &[u8]| descriptor.and_then(|d| d.get(key).ok()).and_then(number);
+        let (ascent, descent) = match (metric(b"Ascent"), metric(b"Descent")) {
+            (Some(ascent), Some(descent)) if ascent > descent => (ascent, descent),
+            _ => (800.0, -200.0),
+        };
+        let default_width = metric(b"MissingWidth")
+            .filter(|&w| w > 0.0)
+            .unwrap_or(default_width);
+        Some(Self {
+            encoding,
+            two_byte,
+            widths,
+            default_width,
+            ascent: ascent / 1000.0,
+            descent: descent / 1000.0,
+        })
+    }
+
+    /// Width of `code` in text space units for a font size of 1.
+    fn width(&self, code: u32) -> f64 {
+        self.widths
+            .get(&code)
+            .copied()
+            .unwrap_or(self.default_width)
+            / 1000.0
+    }
+}
+
+/// A character of the text layer, with its box in pixels.
+struct Glyph {
+    text: String,
+    bbox: BoundingBox,
+    baseline: f64,
+    /// Shown in render mode 3, as OCR tools lay their text over a scan.
+    invisible: bool,
+}
+
+/// Text state of the current text object.
+#[derive(Clone)]
+struct TextState {
+    matrix: Matrix,
+    line_matrix: Matrix,
+    font: Option<Vec<u8>>,
+    size: f64,
+    scaling: f64,
+    leading: f64,
+    char_spacing: f64,
+    word_spacing: f64,
+    rise: f64,
+    render_mode: i64,
+}
+
+impl Default for TextState {
+    fn default() -> Self {
+        Self {
+            matrix: Matrix::IDENTITY,
+            line_matrix: Matrix::IDENTITY,
+            font: None,
+            size: 0.0,
+            scaling: 1.0,
+            leading: 0.0,
+            char_spacing: 0.0,
+            word_spacing: 0.0,
+            rise: 0.0,
+            render_mode: 0,
+        }
+    }
+}
+
+/// Walks a page's content, collecting the text layer and the images to draw.
+struct Interpreter<'a> {
+    document: &'a Document,
+    fonts: HashMap<Vec<u8>, Option<Font<'a>>>,
+    glyphs: Vec<Glyph>,
+    images: Vec<(&'a Stream, Matrix)>,
+}
+
+impl<'a> Interpreter<'a> {
+    fn new(document: &'a Document) -> Self {
+        Self {
+            document,
+            fonts: HashMap::new(),
+            glyphs: Vec::new(),
+            images: Vec::new(),
+        }
+    }
+
+    /// Looks up `name` in a resource `category` such as `Font` or `XObject`.
+    fn resource(
+        &self,
+        resources: &[&'a Dictionary],
+        category: &[u8],
+        name: &[u8],
+    ) -> Option<(Option<ObjectId>, &'a Object)> {
+        resources.iter().find_map(|dictionary| {
+            let entries = resolve(self.document, dictionary.get(category).ok()?)
+                .as_dict()
+                .ok()?;
+            let entry = entries.get(name).ok()?;
+            match entry {
+                Object::Reference(id) => Some((Some(*id), self.document.get_object(*id).ok()?)),
+                object => Some((None, object)),
+            }
+        })
+    }
+
+    /// Loads a font once per object, keyed by its object id or, inline, its resource name.
+    fn font_key(&mut self, resources: &[&'a Dictionary], name: &[u8]) -> Option<Vec<u8>> {
+        let (id, object) = self.resource(resources, b"Font", name)?;
+        let key = match id {
+            Some((number, generation)) => format!("{} {} R", number, generation).into_bytes(),
+            None => name.to_vec(),
+        };
+        if !self.fonts.contains_key(&key) {
+            let font = object
+                .as_dict()
+                .ok()
+                .and_then(|font| Font::load(self.document, font));
+            self.fonts.insert(key.clone(), font);
+        }
+        Some(key)
+    }
+
+    fn run(
+        &mut self,
+        operations: &[Operation],
+        resources: &[&'a Dictionary],
+        mut ctm: Matrix,
+        depth: usize,
+    ) {
+        let mut stack: Vec<Matrix> = Vec::new();
+        let mut text = TextState::default();
+        for operation in operations {
+            let operands = &operation.operands;
+            let first = || operands.first().and_then(number).unwrap_or(0.0);
+            match operation.operator.as_str() {
+                "q" => stack.push(ctm),
+                "Q" => ctm = stack.pop().unwrap_or(ctm),
+                "cm" => {
+                    if let Some(matrix) = Matrix::from_operands(operands) {
+                        ctm = matrix.then(ctm);
+                    }
+                }
+                "BT" => {
+                    text.matrix = Matrix::IDENTITY;
+                    text.line_matrix = Matrix::IDENTITY;
+                }
+                "Tf" => {
+                    text.font = operands
+                        .first()
+                        .and_then(|name| name.as_name().ok())
+                        .and_then(|name| self.font_key(resources, name));
+                    text.size = operands.get(1).and_then(number).unwrap_or(0.0);
+                }
+                "Tz" => text.scaling = first() / 100.0,
+                "TL" => text.leading = first(),
+                "Tc" => text.char_spacing = first(),
+                "Tw" => text.word_spacing = first(),
+                "Ts" => text.rise = first(),
+                "Tr" => text.render_mode = first() as i64,
+                "Td" | "TD" => {
+                    let y = operands.get(1).and_then(number).unwrap_or(0.0);
+                    if operation.operator == "TD" {
+                        text.leading = -y;
+                    }
+                    text.line_matrix = Matrix::translate(first(), y).then(text.line_matrix);
+                    text.matrix = text.line_matrix;
+                }
+                "Tm" => {
+                    if let Some(matrix) = Matrix::from_operands(operands) {
+                        text.line_matrix = matrix;
+                        text.matrix = matrix;
+                    }
+                }
+                "T*" => {
+                    text.line_matrix = Matrix::translate(0.0, -text.leading).then(text.line_matrix);
+                    text.matrix = text.line_matrix;
+                }
+                "Tj" | "'" | "\"" | "TJ" => {
+                    if operation.operator == "\"" {
+                        text.word_spacing = first();
+                        text.char_spacing = operands.get(1).and_then(number).unwrap_or(0.0);
+                    }
+                    if operation.operator != "Tj" && operation.operator != "TJ" {
+                        text.line_matrix =
+                            Matrix::translate(0.0, -text.leading).then(text.line_matrix);
+                        text.matrix = text.line_matrix;
+                    }
+                    for operand in operands {
+                        match operand {
+                            Object::String(bytes, _) => self.show(bytes, &mut text, ctm),
+                            Object::Array(items) => {
+                                for item in items {
+                                    match item {
+                                        Object::String(bytes, _) => {
+                                            self.show(bytes, &mut text, ctm)
+                                        }
+                                        item => {
+                                            let shift = -number(item).unwrap_or(0.0) / 1000.0
+                                                * text.size
+                                                * text.scaling;
+                                            text.matrix =
+                                                Matrix::translate(shift, 0.0).then(text.matrix);
+                                        }
+                                    }
+                                }
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                "Do" => {
+                    let Some((_, object)) = operands
+                        .first()
+                        .and_then(|name| name.as_name().ok())
+                        .and_then(|name| self.resource(resources, b"XObject", name))
+                    else {
+                        continue;
+                    };
+                    let Ok(stream) = object.as_stream() else {
+                        continue;
+                    };
+                    match stream.dict.get(b"Subtype").and_then(Object::as_name) {
+                        Ok(b"Image") => self.images.push((stream, ctm)),
+                        Ok(b"Form") if depth < MAX_DEPTH => {
+                            self.run_form(stream, resources, ctm, depth)
+                        }
+                        _ => {}
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn run_form(
+        &mut self,
+        form: &'a Stream,
+        resources: &[&'a Dictionary],
+        ctm: Matrix,
+        depth: usize,
+    ) {
+        let Ok(content) = form
+            .decompressed_content()
+            .or_else(|_| Ok::<_, lopdf::Error>(form.content.clone()))
+            .and_then(|data| Content::decode(&data))
+        else {
+            return;
+        };
+        let matrix = form
+            .dict
+            .get(b"Matrix")
+            .ok()
+            .and_then(|m| m.as_array().ok())
+            .and_then(|m| Matrix::from_operands(m))
+            .unwrap_or(Matrix::IDENTITY);
+        // A form's own resources come first, then the page's
+        let own = form
+            .dict
+            .get(b"Resources")
+            .ok()
+            .and_then(|r| resolve(self.document, r).as_dict().ok());
+        let resources: Vec<&'a Dictionary> =
+            own.into_iter().chain(resources.iter().copied()).collect();
+        self.run(&content.operations, &resources, matrix.then(ctm), depth + 1);
+    }
+
+    /// Places each character of a shown string and advances the text matrix past it.
+    fn show(&mut self, bytes: &[u8], text: &mut TextState, ctm: Matrix) {
+        let Some(font) = text
+            .font
+            .as_ref()
+            .and_then(|key| self.fonts.get(key)?.as_ref())
+        else {
+            return;
+        };
+        let code_length = if font.two_byte { 2 } else { 1 };
+        for code_bytes in bytes.chunks(code_length) {
+            let code = code_bytes
+                .iter()
+                .fold(0u32, |code, &b| code << 8 | b as u32);
+            let advance = font.width(code) * text.size * text.scaling;
+            let to_pixels = text.matrix.then(ctm);
+            let (left, top, right, bottom) = to_pixels.bounds(
+                0.0,
+                text.rise + font.descent * text.size,
+                advance,
+                text.rise + font.ascent * text.size,
+            );
+            let (_, baseline) = to_pixels.apply(0.0, text.rise);
+            if let Ok(decoded) = Document::decode_text(&font.encoding, code_bytes) {
+                self.glyphs.push(Glyph {
+                    text: decoded,
+                    bbox: BoundingBox::new(left, top, right - left, bottom - top),
+                    baseline,
+                    invisible: text.render_mode == 3,
+                });
+            }
+
+            let mut spacing = text.char_spacing;
+            if code_length == 1 && code == 0x20 {
+                spacing += text.word_spacing;
+            }
+            text.matrix =
+                Matrix::translate(advance + spacing * text.scaling, 0.0).then(text.matrix);
+        }
+    }
+}
+
+/// Groups glyphs, in content order, into words and lines. Words end at spaces and gaps wider
+/// than a third of the text height; lines end where the baseline moves or text goes back.
+fn glyph_lines(glyphs: &[Glyph]) -> Vec<Line> {
+    let mut lines = Vec::new();
+    let mut words: Vec<Word> = Vec::new();
+    let mut word: Option<(String, BoundingBox)> = None;
+    let mut previous: Option<&Glyph> = None;
+
+    let end_word = |word: &mut Option<(String, BoundingBox)>, words: &mut Vec<Word>| {
+        if let Some((text, bbox)) = word.take() {
+            words.push(Word::new(text, bbox, 1.0));
+        }
+    };
+    for glyph in glyphs {
+        if let Some(previous) = previous {
+            let height = previous.bbox.height.max(glyph.bbox.height);
+            let new_line = (glyph.baseline - previous.baseline).abs() > height / 2.0
+                || glyph.bbox.x < previous.bbox.x - height / 2.0;
+            if new_line {
+                end_word(&mut word, &mut words);
+                if !words.is_empty() {
+                    lines.push(Line::from_words(std::mem::take(&mut words)));
+                }
+            } else if glyph.bbox.x - previous.bbox.right() > height / 3.0 {
+                end_word(&mut word, &mut words);
+            }
+        }
+        previous = Some(glyph);
+
+        if glyph.text.trim().is_empty() {
+            end_word(&mut word, &mut words);
+            continue;
+        }
+        match &mut word {
+            Some((text, bbox)) => {
+                text.push_str(&glyph.text);
+                *bbox = bbox.union(&glyph.bbox);
+            }
+            None => word = Some((glyph.text.clone(), glyph.bbox)),
+        }
+    }
+    end_word(&mut word, &mut words);
+    if !words.is_empty() {
+        lines.push(Line::from_words(words));
+    }
+    lines
+}
+
+/// Decodes CCITT Group 4, or Group 3 with end-of-line codes, into rows of 1-bit samples as
+/// `CCITTFaxDecode` outputs them: 0 for black unless `BlackIs1` is set.
+fn decode_fax(data: &[u8], params: Option<&Dictionary>, height: u32) -> Option<Vec<u8>> {
+    let get = |key: &[u8]| params.and_then(|params| params.get(key).ok());
+    let k = get(b"K").and_then(number).unwrap_or(0.0);
+    let columns = get(b"Columns").and_then(number).unwrap_or(1728.0) as u16;
+    let black_is_1 = get(b"BlackIs1")
+        .and_then(|b| b.as_bool().ok())
+        .unwrap_or(false);
+
+    let row_bytes = (columns as usize).div_ceil(8);
+    let mut rows = Vec::with_capacity(row_bytes * height as usize);
+    let push_row = |transitions: &[u16]| {
+        let mut row = vec![0u8; row_bytes];
+        for (x, color) in pels(transitions, columns).enumerate() {
+            if (color == Color::Black) == black_is_1 {
+                row[x / 8] |= 0x80 >> (x % 8);
+            }
+        }
+        rows.extend(row);
+    };
+    let bytes = data.iter().copied();
+    if k < 0.0 {
+        decode_g4(bytes, columns, u16::try_from(height).ok(), push_row)?;
+    } else if k == 0.0 {
+        decode_g3(bytes, push_row)?;
+    } else {
+        // Mixed 1D and 2D Group 3
+        return None;
+    }
+    Some(rows)
+}
+
+/// Decodes an image XObject to RGB.
+fn decode_image(document: &Document, stream: &Stream) -> Result<DynamicImage> {
+    let dict = &stream.dict;
+    let filters = stream.filters().unwrap_or_default();
+    let fax = match filters.last().copied() {
+        Some(b"DCTDecode") if filters.len() == 1 => {
+            return Ok(image::load_from_memory_with_format(
+                &stream.content,
+                ImageFormat::Jpeg,
+            )?);
+        }
+        Some(b"CCITTFaxDecode") if filters.len() == 1 => true,
+        Some(b"FlateDecode" | b"LZWDecode" | b"ASCII85Decode") | None => false,
+        Some(filter) => {
+            return Err(OcrError::InvalidImage(format!(
+                "unsupported PDF image encoding {}",
+                String::from_utf8_lossy(filter)
+            )))
+        }
+    };
+
+    let get = |key: &[u8]| dict.get(key).ok().and_then(number);
+    let width = get(b"Width").unwrap_or(0.0) as u32;
+    let height = get(b"Height").unwrap_or(0.0) as u32;
+    let data = if fax {
+        let params = dict
+            .get(b"DecodeParms")
+            .map(|params| resolve(document, params))
+            .ok()
+            .and_then(|params| match params {
+                Object::Array(params) => params.first().map(|p| resolve(document, p)),
+                params => Some(params),
+            })
+            .and_then(|params| params.as_dict().ok());
+        decode_fax(&stream.content, params, height).ok_or_else(|| {
+            OcrError::InvalidImage("unsupported or damaged CCITT fax image".to_string())
+        })?
+    } else {
+        stream.get_plain_content().map_err(invalid_pdf)?
+    };
+    let is_mask = dict
+        .get(b"ImageMask")
+        .and_then(Object::as_bool)
+        .unwrap_or(false);
+    let bits = if is_mask || fax {
+        1
+    } else {
+        get(b"BitsPerComponent").unwrap_or(8.0) as u32
+    };
+    let color_space = match dict.get(b"ColorSpace") {
+        Ok(space) if !is_mask => ColorSpace::parse(document, resolve(document, space)),
+        _ => ColorSpace::Gray,
+    };
+    // A Decode array starting with 1 0 inverts gray images and masks
+    let inverted = dict
+        .get(b"Decode")
+        .and_then(Object::as_array)
+        .ok()
+        .and_then(|decode| Some((number(decode.first()?)?, number(decode.get(1)?)?)))
+        .is_some_and(|(low, high)| low > high);
+    if width == 0 || height == 0 || !matches!(bits, 1 | 2 | 4 | 8 | 16) {
+        return Err(OcrError::InvalidImage(format!(
+            "unsupported PDF image: {}x{} at {} bits",
+            width, height, bits
+        )));
+    }
+
+    let components = color_space.components();
+    let row_bytes = (width as usize * components * bits as usize).div_ceil(8);
+    let max = ((1u32 << bits) - 1) as f64;
+    let sample = |row: &[u8], index: usize| -> u32 {
+        match bits {
+            8 => row.get(index).copied().unwrap_or(0) as u32,
+            16 => row.get(2 * index).copied().unwrap_or(0) as u32,
+            _ => {
+                let bit = index * bits as usize;
+                let byte = row.get(bit / 8).copied().unwrap_or(0) as u32;
+                (byte >> (8 - bits as usize - bit % 8)) & ((1 << bits) - 1)
+            }
+        }
+    };
+    let scaled = |value: u32| -> u8 {
+        if bits == 16 {
+            value as u8
+        } else {
+            (value as f64 * 255.0 / max).round() as u8
+        }
+    };
+
+    let mut rgb = RgbImage::new(width, height);
+    for (y, row) in data.chunks(row_bytes).take(height as usize).enumerate() {
+        for x in 0..width as usize {
+            let at = |c: usize| sample(row, x * components + c);
+            let pixel = match &color_space {
+                ColorSpace::Gray => {
+                    let mut value = scaled(at(0));
+                    if is_mask {
+                        // Mask samples of 0 paint black, 1 leave the page white
+                        value = if (at(0) == 0) != inverted { 0 } else { 255 };
+                    } else if inverted {
+                        value = 255 - value;
+                    }
+                    [value; 3]
+                }
+                ColorSpace::Rgb => [scaled(at(0)), scaled(at(1)), scaled(at(2))],
+                ColorSpace::Cmyk => {
+                    let k = 1.0 - scaled(at(3)) as f64 / 255.0;
+                    let channel = |c: u32| ((255 - scaled(c)) as f64 * k).round() as u8;
+                    [channel(at(0)), channel(at(1)), channel(at(2))]
+                }
+                ColorSpace::Indexed(palette) => {
+                    let index = at(0) as usize;
+                    palette.get(index).copied().unwrap_or([0, 0, 0])
+                }
+            };
+            rgb.put_pixel(x as u32, y as u32, Rgb(pixel));
+        }
+    }
+    Ok(DynamicImage::ImageRgb8(rgb))
+}
+
+/// Colour spaces of image XObjects; calibrated and ICC spaces are read as their device
+/// equivalents.
+enum ColorSpace {
+    Gray,
+    Rgb,
+    Cmyk,
+    Indexed(Vec<[u8; 3]>),
+}
+
+impl ColorSpace {
+    fn parse(document: &Document, space: &Object) -> Self {
+        match space {
+            Object::Name(name) => match name.as_slice() {
+                b"DeviceRGB" | b"CalRGB" | b"RGB" => ColorSpace::Rgb,
+                b"DeviceCMYK" | b"CMYK" => ColorSpace::Cmyk,
+                _ => ColorSpace::Gray,
+            },
+            Object::Array(items) => {
+                let family = items.first().and_then(|f| f.as_name().ok());
+                match family {
+                    Some(b"ICCBased") => {
+                        let n = items
+                            .get(1)
+                            .map(|profile| resolve(document, profile))
+                            .and_then(|profile| profile.as_stream().ok())
+                            .and_then(|profile| profile.dict.get(b"N").ok())
+                            .and_then(number);
+                        match n {
+                            Some(n) if n >= 4.0 => ColorSpace::Cmyk,
+                            Some(n) if n >= 3.0 => ColorSpace::Rgb,
+                            _ => ColorSpace::Gray,
+                        }
+                    }
+                    Some(b"Indexed" | b"I") => {
+                        let base = items
+                            .get(1)
+                            .map(|base| ColorSpace::parse(document, resolve(document, base)))
+                            .unwrap_or(ColorSpace::Rgb);
+                        let lookup = items.get(3).map(|l| resolve(document, l));
+                        let table = match lookup {
+                            Some(Object::String(bytes, _)) => bytes.clone(),
+                            Some(Object::Stream(stream)) => {
+                                stream.get_plain_content().unwrap_or_default()
+                            }
+                            _ => Vec::new(),
+                        };
+                        let palette = match base {
+                            ColorSpace::Rgb => {
+                                table.chunks_exact(3).map(|c| [c[0], c[1], c[2]]).collect()
+                            }
+                            ColorSpace::Cmyk => table
+                                .chunks_exact(4)
+                                .map(|c| {
+                                    let k = 1.0 - c[3] as f64 / 255.0;
+                                    let channel = |v: u8| ((255 - v) as f64 * k).round() as u8;
+                                    [channel(c[0]), channel(c[1]), channel(c[2])]
+                                })
+                                .collect(),
+                            _ => table.iter().map(|&v| [v; 3]).collect(),
+                        };
+                        ColorSpace::Indexed(palette)
+                    }
+                    Some(b"CalRGB") => ColorSpace::Rgb,
+                    _ => ColorSpace::Gray,
+                }
+            }
+            _ => ColorSpace::Gray,
+        }
+    }
+
+    fn components(&self) -> usize {
+        match self {
+            ColorSpace::Gray | ColorSpace::Indexed(_) => 1,
+            ColorSpace::Rgb => 3,
+            ColorSpace::Cmyk => 4,
+        }
+    }
+}
+
+/// Draws `image`, which fills the unit square of `matrix`, onto the page.
+fn draw_image(canvas: &mut RgbImage, image: &DynamicImage, matrix: Matrix) {
+    let Some(inverse) = matrix.invert() else {
+        return;
+    };
+    let image = image.to_rgb8();
+    let (width, height) = (image.width() as f64, image.height() as f64);
+    let (left, top, right, bottom) = matrix.bounds(0.0, 0.0, 1.0, 1.0);
+    let x_range = left.max(0.0).floor() as u32..right.min(canvas.width() as f64).ceil() as u32;
+    let y_range = top.max(0.0).floor() as u32..bottom.min(canvas.height() as f64).ceil() as u32;
+    for y in y_range {
+        for x in x_range.clone() {
+            let (u, v) = inverse.apply(x as f64 + 0.5, y as f64 + 0.5);
+            if !(0.0..1.0).contains(&u) || !(0.0..1.0).contains(&v) {
+                continue;
+            }
+            // Image rows run top to bottom, the unit square bottom to top
+            let source_x = (u * width) as u32;
+            let source_y = ((1.0 - v) * height).min(height - 1.0) as u32;
+            canvas.put_pixel(x, y, *image.get_pixel(source_x, source_y));
+        }
+    }
+}
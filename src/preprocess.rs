@@ -0,0 +1,678 @@
+use image::imageops::{self, FilterType};
+use image::{DynamicImage, GrayImage, Luma, Rgba, RgbaImage};
+use tracing::{debug, warn};
+
+use crate::result::BoundingBox;
+use crate::OcrOptions;
+
+/// Largest skew, in degrees, [`Preprocessor::deskew`] looks for.
+const MAX_SKEW: f64 = 15.0;
+
+/// Skew estimation runs on a copy no larger than this on either side.
+const SKEW_ESTIMATE_SIZE: u32 = 1000;
+
+/// [`Preprocessor::auto_upscale`] upscales text shorter than this, in pixels, to about
+/// [`TARGET_TEXT_HEIGHT`], by at most [`MAX_AUTO_UPSCALE`].
+const MIN_TEXT_HEIGHT: f64 = 20.0;
+const TARGET_TEXT_HEIGHT: f64 = 32.0;
+const MAX_AUTO_UPSCALE: f64 = 4.0;
+
+/// [`Preprocessor::invert_dark_regions`] judges the background over windows of this fraction
+/// of the shorter image side, and at least [`MIN_REGION_WINDOW`] pixels.
+const REGION_FRACTION: u32 = 8;
+const MIN_REGION_WINDOW: u32 = 32;
+
+/// How [`Preprocessor::binarize`] picks the black/white threshold.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Binarization {
+    /// One global threshold chosen with Otsu's method. Suits evenly lit images.
+    Otsu,
+    /// A threshold per pixel: the mean of the surrounding `window` × `window` pixels minus
+    /// `offset`. Suits scans with shadows or uneven lighting.
+    Adaptive { window: u32, offset: u8 },
+}
+
+/// Image cleanup applied before recognition, set with [`crate::OcrOptions::preprocess`].
+///
+/// Each step is toggled on its own; enabled steps always run in the order of the fields
+/// below. Contrast stretching, denoising and binarization work on a grayscale copy. Boxes
+/// in the result refer to the original image, whatever rotation, upscaling or padding was
+/// applied.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Preprocessor {
+    /// Turns pages scanned or photographed sideways or upside down upright, using Tesseract's
+    /// orientation detection. Skipped with a warning when that fails, e.g. without the `osd`
+    /// traineddata.
+    pub orientation: bool,
+    /// Straightens text skewed by up to 15°, estimated from the horizontal projection profile.
+    pub deskew: bool,
+    /// Inverts images that are mostly dark, such as dark-mode screenshots, so text ends up
+    /// dark on light.
+    pub invert_dark: bool,
+    /// Inverts only the parts of the image that are light on dark, such as a dark-themed
+    /// editor pane next to a light sidebar. Also covers fully dark images.
+    pub invert_dark_regions: bool,
+    pub grayscale: bool,
+    /// Stretches the 1st–99th percentile of brightness to the full range.
+    pub contrast_stretch: bool,
+    /// 3×3 median filter, removing speckles from scans.
+    pub denoise: bool,
+    /// Scale factor for small text; OCR engines do best with glyphs 20–40 px tall.
+    pub upscale: Option<f32>,
+    /// Estimates the text height and upscales, up to 4×, when it is below 20 px. Takes
+    /// precedence over `upscale`.
+    pub auto_upscale: bool,
+    pub binarize: Option<Binarization>,
+    /// White border, in pixels of the preprocessed image, added around the image. Text
+    /// touching the edge is often missed without one.
+    pub padding: u32,
+}
+
+impl Preprocessor {
+    /// Screen captures: light-on-dark regions inverted, grayscale, contrast stretched, small
+    /// UI text upscaled, a thin border.
+    pub fn screenshot() -> Self {
+        Self {
+            invert_dark_regions: true,
+            grayscale: true,
+            contrast_stretch: true,
+            auto_upscale: true,
+            padding: 10,
+            ..Self::default()
+        }
+    }
+
+    /// Scanned pages: straightened, contrast stretched, despeckled and binarized per
+    /// neighbourhood to cope with uneven lighting. Add [`Preprocessor::orientation`] for
+    /// pages that may be sideways or upside down.
+    pub fn scanned_document() -> Self {
+        Self {
+            deskew: true,
+            grayscale: true,
+            contrast_stretch: true,
+            denoise: true,
+            binarize: Some(Binarization::Adaptive {
+                window: 31,
+                offset: 10,
+            }),
+            padding: 10,
+            ..Self::default()
+        }
+    }
+
+    pub fn orientation(mut self, enabled: bool) -> Self {
+        self.orientation = enabled;
+        self
+    }
+
+    pub fn deskew(mut self, enabled: bool) -> Self {
+        self.deskew = enabled;
+        self
+    }
+
+    pub fn invert_dark(mut self, enabled: bool) -> Self {
+        self.invert_dark = enabled;
+        self
+    }
+
+    pub fn invert_dark_regions(mut self, enabled: bool) -> Self {
+        self.invert_dark_regions = enabled;
+        self
+    }
+
+    pub fn grayscale(mut self, enabled: bool) -> Self {
+        self.grayscale = enabled;
+        self
+    }
+
+    pub fn contrast_stretch(mut self, enabled: bool) -> Self {
+        self.contrast_stretch = enabled;
+        self
+    }
+
+    pub fn denoise(mut self, enabled: bool) -> Self {
+        self.denoise = enabled;
+        self
+    }
+
+    pub fn upscale(mut self, factor: Option<f32>) -> Self {
+        self.upscale = factor;
+        self
+    }
+
+    pub fn auto_upscale(mut self, enabled: bool) -> Self {
+        self.auto_upscale = enabled;
+        self
+    }
+
+    pub fn binarize(mut self, binarization: Option<Binarization>) -> Self {
+        self.binarize = binarization;
+        self
+    }
+
+    pub fn padding(mut self, pixels: u32) -> Self {
+        self.padding = pixels;
+        self
+    }
+
+    /// `true` when no step is enabled.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    fn scale(&self) -> f64 {
+        match self.upscale {
+            Some(factor) if factor > 0.0 => factor as f64,
+            _ => 1.0,
+        }
+    }
+
+    /// Runs the enabled steps on `image`. Orientation detection uses the default
+    /// [`OcrOptions`].
+    pub fn apply(&self, image: &DynamicImage) -> DynamicImage {
+        self.prepare(image, &OcrOptions::default()).image
+    }
+
+    /// Runs the enabled steps, remembering how to map boxes back to `image`. Orientation
+    /// detection follows the Tesseract settings and timeout of `options`.
+    pub(crate) fn prepare(&self, image: &DynamicImage, options: &OcrOptions) -> Prepared {
+        let mut transform = Transform {
+            width: image.width(),
+            height: image.height(),
+            quarter_turns: 0,
+            skew: 0.0,
+            scale: self.scale(),
+            padding: self.padding,
+        };
+        let mut image = image.clone();
+
+        if self.orientation {
+            match crate::tesseract::detect_orientation(&image, options) {
+                Ok(degrees) => {
+                    transform.quarter_turns = (degrees / 90) % 4;
+                    image = match transform.quarter_turns {
+                        1 => image.rotate90(),
+                        2 => image.rotate180(),
+                        3 => image.rotate270(),
+                        _ => image,
+                    };
+                }
+                Err(err) => warn!("skipping orientation correction: {}", err),
+            }
+        }
+        if self.deskew {
+            let skew = estimate_skew(&image);
+            debug!("estimated skew correction: {:.2}°", skew);
+            if skew != 0.0 {
+                transform.skew = skew;
+                image = rotate_about_center(&image, skew);
+            }
+        }
+        if self.invert_dark && is_dark(&image.to_luma8()) {
+            image.invert();
+        }
+        if self.invert_dark_regions {
+            image = invert_dark_regions(&image);
+        }
+        if self.grayscale {
+            image = DynamicImage::ImageLuma8(image.to_luma8());
+        }
+        if self.contrast_stretch {
+            image = DynamicImage::ImageLuma8(contrast_stretch(image.to_luma8()));
+        }
+        if self.denoise {
+            image = DynamicImage::ImageLuma8(median_3x3(&image.to_luma8()));
+        }
+        if self.auto_upscale {
+            transform.scale = auto_scale(&image.to_luma8());
+        }
+        if transform.scale != 1.0 {
+            let width = ((image.width() as f64 * transform.scale).round() as u32).max(1);
+            let height = ((image.height() as f64 * transform.scale).round() as u32).max(1);
+            image = image.resize_exact(width, height, FilterType::CatmullRom);
+        }
+        match self.binarize {
+            Some(Binarization::Otsu) => {
+                let gray = image.to_luma8();
+                let threshold = otsu_threshold(&gray);
+                image = DynamicImage::ImageLuma8(threshold_image(&gray, |_, _| threshold));
+            }
+            Some(Binarization::Adaptive { window, offset }) => {
+                image =
+                    DynamicImage::ImageLuma8(adaptive_threshold(&image.to_luma8(), window, offset));
+            }
+            None => {}
+        }
+        if self.padding > 0 {
+            image = pad(&image, self.padding);
+        }
+
+        Prepared { image, transform }
+    }
+}
+
+/// A preprocessed image and the geometry needed to map its boxes back.
+pub(crate) struct Prepared {
+    pub image: DynamicImage,
+    pub transform: Transform,
+}
+
+/// Geometric steps applied by [`Preprocessor::prepare`], in order.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Transform {
+    /// Size of the original image.
+    width: u32,
+    height: u32,
+    /// Clockwise quarter turns.
+    quarter_turns: u32,
+    /// Clockwise degrees, about the center.
+    skew: f64,
+    scale: f64,
+    padding: u32,
+}
+
+impl Transform {
+    /// Total clockwise rotation applied to the original image, in degrees.
+    pub fn rotation(&self) -> f64 {
+        self.quarter_turns as f64 * 90.0 + self.skew
+    }
+
+    /// Original image size.
+    pub fn dimensions(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Maps a box in the preprocessed image to the smallest box covering it in the original
+    /// image, clipped to the image.
+    pub fn original_box(&self, bbox: BoundingBox) -> BoundingBox {
+        // Size after the quarter turns; deskewing keeps the size
+        let (width, height) = if self.quarter_turns % 2 == 1 {
+            (self.height as f64, self.width as f64)
+        } else {
+            (self.width as f64, self.height as f64)
+        };
+
+        let padding = self.padding as f64;
+        let mut bbox = BoundingBox::new(
+            (bbox.x - padding) / self.scale,
+            (bbox.y - padding) / self.scale,
+            bbox.width / self.scale,
+            bbox.height / self.scale,
+        );
+
+        if self.skew != 0.0 {
+            let center = (width / 2.0, height / 2.0);
+            let corners = [
+                (bbox.x, bbox.y),
+                (bbox.right(), bbox.y),
+                (bbox.x, bbox.bottom()),
+                (bbox.right(), bbox.bottom()),
+            ]
+            .map(|corner| rotate_point(corner, center, -self.skew));
+            let (mut left, mut top) = (f64::MAX, f64::MAX);
+            let (mut right, mut bottom) = (f64::MIN, f64::MIN);
+            for (x, y) in corners {
+                (left, top) = (left.min(x), top.min(y));
+                (right, bottom) = (right.max(x), bottom.max(y));
+            }
+            bbox = BoundingBox::new(left, top, right - left, bottom - top);
+        }
+        let bbox = clip(bbox, width, height);
+
+        // Undo the quarter turns one at a time, last first
+        (0..self.quarter_turns).rev().fold(bbox, |b, turn| {
+            // Height of the image before this turn
+            let before = if turn % 2 == 0 {
+                self.height
+            } else {
+                self.width
+            } as f64;
+            BoundingBox::new(b.y, before - b.right(), b.height, b.width)
+        })
+    }
+}
+
+fn clip(bbox: BoundingBox, width: f64, height: f64) -> BoundingBox {
+    let x = bbox.x.clamp(0.0, width);
+    let y = bbox.y.clamp(0.0, height);
+    let right = bbox.right().clamp(0.0, width);
+    let bottom = bbox.bottom().clamp(0.0, height);
+    BoundingBox::new(x, y, right - x, bottom - y)
+}
+
+/// Rotates `point` clockwise by `degrees` about `center`, in image coordinates.
+fn rotate_point(point: (f64, f64), center: (f64, f64), degrees: f64) -> (f64, f64) {
+    let (sin, cos) = degrees.to_radians().sin_cos();
+    let (dx, dy) = (point.0 - center.0, point.1 - center.1);
+    (
+        center.0 + dx * cos - dy * sin,
+        center.1 + dx * sin + dy * cos,
+    )
+}
+
+/// Rotates the image clockwise by `degrees` about its center, keeping its size. Uncovered
+/// corners are filled with the background: white, or black for dark images.
+fn rotate_about_center(image: &DynamicImage, degrees: f64) -> DynamicImage {
+    let source = image.to_rgba8();
+    let (width, height) = source.dimensions();
+    let fill = if is_dark(&image.to_luma8()) {
+        Rgba([0, 0, 0, 255])
+    } else {
+        Rgba([255, 255, 255, 255])
+    };
+    let center = (width as f64 / 2.0, height as f64 / 2.0);
+
+    let rotated = RgbaImage::from_fn(width, height, |x, y| {
+        // Bilinear sample of the source pixel that lands here
+        let (sx, sy) = rotate_point((x as f64 + 0.5, y as f64 + 0.5), center, -degrees);
+        let (sx, sy) = (sx - 0.5, sy - 0.5);
+        let (x0, y0) = (sx.floor(), sy.floor());
+        let (fx, fy) = (sx - x0, sy - y0);
+        let sample = |x: f64, y: f64| {
+            if x < 0.0 || y < 0.0 || x >= width as f64 || y >= height as f64 {
+                fill
+            } else {
+                *source.get_pixel(x as u32, y as u32)
+            }
+        };
+        let (a, b) = (sample(x0, y0), sample(x0 + 1.0, y0));
+        let (c, d) = (sample(x0, y0 + 1.0), sample(x0 + 1.0, y0 + 1.0));
+        Rgba(std::array::from_fn(|i| {
+            let top = a[i] as f64 * (1.0 - fx) + b[i] as f64 * fx;
+            let bottom = c[i] as f64 * (1.0 - fx) + d[i] as f64 * fx;
+            (top * (1.0 - fy) + bottom * fy).round() as u8
+        }))
+    });
+
+    match image {
+        DynamicImage::ImageLuma8(_) => {
+            DynamicImage::ImageLuma8(DynamicImage::ImageRgba8(rotated).to_luma8())
+        }
+        _ => DynamicImage::ImageRgba8(rotated),
+    }
+}
+
+/// Upscale factor bringing the estimated text height to [`TARGET_TEXT_HEIGHT`], or 1.0 when
+/// the text is tall enough or none is found.
+fn auto_scale(gray: &GrayImage) -> f64 {
+    match estimate_text_height(gray) {
+        Some(height) if height < MIN_TEXT_HEIGHT => {
+            debug!("estimated text height: {:.1}px", height);
+            (TARGET_TEXT_HEIGHT / height).min(MAX_AUTO_UPSCALE)
+        }
+        _ => 1.0,
+    }
+}
+
+/// Median height of the bands of rows containing ink, which for text are mostly lines.
+fn estimate_text_height(gray: &GrayImage) -> Option<f64> {
+    let threshold = otsu_threshold(gray);
+    let dark_background = is_dark(gray);
+    let mut heights = Vec::new();
+    let mut run = 0u32;
+    for y in 0..gray.height() {
+        let ink = (0..gray.width())
+            .filter(|&x| (gray.get_pixel(x, y)[0] > threshold) == dark_background)
+            .count();
+        // A lone pixel is noise, and a row that is nearly all ink is a rule or a border
+        if ink >= 2 && ink * 10 < gray.width() as usize * 9 {
+            run += 1;
+        } else {
+            if run >= 3 {
+                heights.push(run);
+            }
+            run = 0;
+        }
+    }
+    if run >= 3 {
+        heights.push(run);
+    }
+    if heights.is_empty() {
+        return None;
+    }
+    heights.sort_unstable();
+    Some(heights[heights.len() / 2] as f64)
+}
+
+/// Clockwise rotation, in degrees, that best straightens the text: the one making the
+/// horizontal projection profile of the ink sharpest. 0 when the text is already straight
+/// or there is none.
+fn estimate_skew(image: &DynamicImage) -> f64 {
+    let mut gray = image.to_luma8();
+    if gray.width() > SKEW_ESTIMATE_SIZE || gray.height() > SKEW_ESTIMATE_SIZE {
+        gray = DynamicImage::ImageLuma8(gray)
+            .resize(SKEW_ESTIMATE_SIZE, SKEW_ESTIMATE_SIZE, FilterType::Triangle)
+            .to_luma8();
+    }
+
+    let threshold = otsu_threshold(&gray);
+    let dark_background = is_dark(&gray);
+    let ink: Vec<(f64, f64)> = gray
+        .enumerate_pixels()
+        .filter(|(_, _, p)| (p[0] > threshold) == dark_background)
+        .map(|(x, y, _)| (x as f64, y as f64))
+        .collect();
+    // Nothing to go by, or so much "ink" it is really the background
+    if ink.is_empty() || ink.len() * 2 > (gray.width() * gray.height()) as usize {
+        return 0.0;
+    }
+
+    let center = (gray.width() as f64 / 2.0, gray.height() as f64 / 2.0);
+    let rows = (gray.width() + gray.height()) as usize * 2;
+    let sharpness = |degrees: f64| {
+        let mut profile = vec![0u64; rows];
+        for &point in &ink {
+            let (_, y) = rotate_point(point, center, degrees);
+            let row = (y + rows as f64 / 4.0).clamp(0.0, rows as f64 - 1.0) as usize;
+            profile[row] += 1;
+        }
+        profile.iter().map(|&count| count * count).sum::<u64>()
+    };
+    let best_in = |from: f64, to: f64, step: f64| {
+        let steps = ((to - from) / step).round() as i64;
+        (0..=steps)
+            .map(|i| from + i as f64 * step)
+            .map(|degrees| (degrees, sharpness(degrees)))
+            .max_by(|a, b| a.1.cmp(&b.1).then(b.0.abs().total_cmp(&a.0.abs())))
+            .map_or(0.0, |(degrees, _)| degrees)
+    };
+
+    let coarse = best_in(-MAX_SKEW, MAX_SKEW, 0.5);
+    let fine = best_in(coarse - 0.5, coarse + 0.5, 0.05);
+    if fine.abs() < 0.1 {
+        0.0
+    } else {
+        fine
+    }
+}
+
+/// Whether most of the image is darker than mid-gray.
+fn is_dark(gray: &GrayImage) -> bool {
+    let pixels = gray.as_raw();
+    if pixels.is_empty() {
+        return false;
+    }
+    let total: u64 = pixels.iter().map(|&p| p as u64).sum();
+    total / (pixels.len() as u64) < 128
+}
+
+fn histogram(gray: &GrayImage) -> [u64; 256] {
+    let mut histogram = [0u64; 256];
+    for &p in gray.as_raw() {
+        histogram[p as usize] += 1;
+    }
+    histogram
+}
+
+fn contrast_stretch(mut gray: GrayImage) -> GrayImage {
+    let histogram = histogram(&gray);
+    let total: u64 = histogram.iter().sum();
+    let percentile = |fraction: f64| {
+        let target = (total as f64 * fraction) as u64;
+        let mut seen = 0;
+        for (value, count) in histogram.iter().enumerate() {
+            seen += count;
+            if seen > target {
+                return value as u8;
+            }
+        }
+        255
+    };
+    let (low, high) = (percentile(0.01), percentile(0.99));
+    if high <= low {
+        return gray;
+    }
+    let range = (high - low) as f32;
+    for p in gray.pixels_mut() {
+        let stretched = (p[0].saturating_sub(low) as f32 / range * 255.0).min(255.0);
+        p[0] = stretched as u8;
+    }
+    gray
+}
+
+fn median_3x3(gray: &GrayImage) -> GrayImage {
+    let (width, height) = gray.dimensions();
+    GrayImage::from_fn(width, height, |x, y| {
+        let mut window = [0u8; 9];
+        let mut i = 0;
+        for dy in -1i64..=1 {
+            for dx in -1i64..=1 {
+                let sx = (x as i64 + dx).clamp(0, width as i64 - 1) as u32;
+                let sy = (y as i64 + dy).clamp(0, height as i64 - 1) as u32;
+                window[i] = gray.get_pixel(sx, sy)[0];
+                i += 1;
+            }
+        }
+        window.sort_unstable();
+        Luma([window[4]])
+    })
+}
+
+/// Otsu's method: the threshold maximizing the variance between the two classes.
+fn otsu_threshold(gray: &GrayImage) -> u8 {
+    let histogram = histogram(gray);
+    let total: u64 = histogram.iter().sum();
+    let sum_all: f64 = histogram
+        .iter()
+        .enumerate()
+        .map(|(value, &count)| value as f64 * count as f64)
+        .sum();
+
+    let (mut best, mut best_variance) = (0u8, 0.0f64);
+    let (mut weight_low, mut sum_low) = (0u64, 0.0f64);
+    for (value, &count) in histogram.iter().enumerate() {
+        weight_low += count;
+        if weight_low == 0 {
+            continue;
+        }
+        let weight_high = total - weight_low;
+        if weight_high == 0 {
+            break;
+        }
+        sum_low += value as f64 * count as f64;
+        let mean_low = sum_low / weight_low as f64;
+        let mean_high = (sum_all - sum_low) / weight_high as f64;
+        let variance = weight_low as f64 * weight_high as f64 * (mean_low - mean_high).powi(2);
+        if variance > best_variance {
+            best_variance = variance;
+            best = value as u8;
+        }
+    }
+    best
+}
+
+/// Pixels above their threshold become white, the rest black.
+fn threshold_image(gray: &GrayImage, threshold: impl Fn(u32, u32) -> u8) -> GrayImage {
+    GrayImage::from_fn(gray.width(), gray.height(), |x, y| {
+        if gray.get_pixel(x, y)[0] > threshold(x, y) {
+            Luma([255])
+        } else {
+            Luma([0])
+        }
+    })
+}
+
+fn adaptive_threshold(gray: &GrayImage, window: u32, offset: u8) -> GrayImage {
+    let means = local_means(gray, window);
+    threshold_image(gray, |x, y| means.get_pixel(x, y)[0].saturating_sub(offset))
+}
+
+/// Mean brightness of the `window` × `window` neighbourhood of every pixel.
+fn local_means(gray: &GrayImage, window: u32) -> GrayImage {
+    let (width, height) = gray.dimensions();
+    let radius = (window / 2).max(1) as i64;
+
+    // Summed-area table, one row and column larger than the image
+    let stride = width as usize + 1;
+    let mut integral = vec![0u64; stride * (height as usize + 1)];
+    for y in 0..height as usize {
+        let mut row = 0u64;
+        for x in 0..width as usize {
+            row += gray.get_pixel(x as u32, y as u32)[0] as u64;
+            integral[(y + 1) * stride + x + 1] = integral[y * stride + x + 1] + row;
+        }
+    }
+
+    GrayImage::from_fn(width, height, |x, y| {
+        let x0 = (x as i64 - radius).max(0) as usize;
+        let y0 = (y as i64 - radius).max(0) as usize;
+        let x1 = (x as i64 + radius + 1).min(width as i64) as usize;
+        let y1 = (y as i64 + radius + 1).min(height as i64) as usize;
+        let sum = integral[y1 * stride + x1] + integral[y0 * stride + x0]
+            - integral[y0 * stride + x1]
+            - integral[y1 * stride + x0];
+        Luma([(sum / ((x1 - x0) * (y1 - y0)) as u64) as u8])
+    })
+}
+
+/// Inverts the pixels whose surroundings are mostly dark, leaving light areas alone.
+fn invert_dark_regions(image: &DynamicImage) -> DynamicImage {
+    let gray = image.to_luma8();
+    let window = (gray.width().min(gray.height()) / REGION_FRACTION).max(MIN_REGION_WINDOW);
+    let means = local_means(&gray, window);
+    let dark = |x: u32, y: u32| means.get_pixel(x, y)[0] < 128;
+
+    match image {
+        DynamicImage::ImageLuma8(_) => {
+            let mut gray = gray;
+            for (x, y, p) in gray.enumerate_pixels_mut() {
+                if dark(x, y) {
+                    p[0] = 255 - p[0];
+                }
+            }
+            DynamicImage::ImageLuma8(gray)
+        }
+        image => {
+            let mut rgba = image.to_rgba8();
+            for (x, y, p) in rgba.enumerate_pixels_mut() {
+                if dark(x, y) {
+                    for channel in &mut p.0[..3] {
+                        *channel = 255 - *channel;
+                    }
+                }
+            }
+            DynamicImage::ImageRgba8(rgba)
+        }
+    }
+}
+
+fn pad(image: &DynamicImage, padding: u32) -> DynamicImage {
+    let (width, height) = (image.width() + 2 * padding, image.height() + 2 * padding);
+    match image {
+        DynamicImage::ImageLuma8(gray) => {
+            let mut canvas = GrayImage::from_pixel(width, height, Luma([255]));
+            imageops::replace(&mut canvas, gray, padding as i64, padding as i64);
+            DynamicImage::ImageLuma8(canvas)
+        }
+        image => {
+            let mut canvas = image::RgbaImage::from_pixel(width, height, image::Rgba([255; 4]));
+            imageops::replace(
+                &mut canvas,
+                &image.to_rgba8(),
+                padding as i64,
+                padding as i64,
+            );
+            DynamicImage::ImageRgba8(canvas)
+        }
+    }
+}
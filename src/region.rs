@@ -0,0 +1,41 @@
+use crate::result::BoundingBox;
+
+/// Pixel rectangle of an image, top-left origin, as passed to
+/// [`crate::OcrEngine::recognize_regions`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Rect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Rect {
+    pub fn new(x: u32, y: u32, width: u32, height: u32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// The part of the rectangle inside a `width` × `height` image, if any.
+    pub(crate) fn clamp_to(&self, width: u32, height: u32) -> Option<Rect> {
+        let right = self.x.saturating_add(self.width).min(width);
+        let bottom = self.y.saturating_add(self.height).min(height);
+        (self.x < right && self.y < bottom)
+            .then(|| Rect::new(self.x, self.y, right - self.x, bottom - self.y))
+    }
+}
+
+impl From<Rect> for BoundingBox {
+    fn from(rect: Rect) -> Self {
+        BoundingBox::new(
+            rect.x as f64,
+            rect.y as f64,
+            rect.width as f64,
+            rect.height as f64,
+        )
+    }
+}
@@ -0,0 +1,459 @@
+use serde::{Deserialize, Serialize};
+
+use crate::charset::CharacterFilter;
+
+/// Axis-aligned rectangle enclosing a recognized element.
+///
+/// All providers report boxes in pixels of the recognized image, with the origin at the
+/// top-left corner and `y` growing downwards. Use [`BoundingBox::normalized`] for a
+/// resolution-independent view.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct BoundingBox {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl BoundingBox {
+    pub fn new(x: f64, y: f64, width: f64, height: f64) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Converts a box expressed as 0–1 fractions of the page, top-left origin, to pixels.
+    pub fn from_normalized(normalized: &BoundingBox, page_width: u32, page_height: u32) -> Self {
+        let (w, h) = (page_width as f64, page_height as f64);
+        BoundingBox::new(
+            normalized.x * w,
+            normalized.y * h,
+            normalized.width * w,
+            normalized.height * h,
+        )
+    }
+
+    /// Same box as 0–1 fractions of the page size, top-left origin.
+    pub fn normalized(&self, page_width: u32, page_height: u32) -> BoundingBox {
+        if page_width == 0 || page_height == 0 {
+            return BoundingBox::default();
+        }
+        let (w, h) = (page_width as f64, page_height as f64);
+        BoundingBox::new(self.x / w, self.y / h, self.width / w, self.height / h)
+    }
+
+    pub fn right(&self) -> f64 {
+        self.x + self.width
+    }
+
+    pub fn bottom(&self) -> f64 {
+        self.y + self.height
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.width <= 0.0 || self.height <= 0.0
+    }
+
+    /// Smallest box containing both `self` and `other`. Empty boxes are ignored.
+    pub fn union(&self, other: &BoundingBox) -> BoundingBox {
+        if self.is_empty() {
+            return *other;
+        }
+        if other.is_empty() {
+            return *self;
+        }
+        let x = self.x.min(other.x);
+        let y = self.y.min(other.y);
+        let right = self.right().max(other.right());
+        let bottom = self.bottom().max(other.bottom());
+        BoundingBox::new(x, y, right - x, bottom - y)
+    }
+
+    fn enclosing<'a>(boxes: impl IntoIterator<Item = &'a BoundingBox>) -> BoundingBox {
+        boxes
+            .into_iter()
+            .fold(BoundingBox::default(), |acc, b| acc.union(b))
+    }
+}
+
+/// A single recognized word.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Word {
+    pub text: String,
+    pub bbox: BoundingBox,
+    /// Recognition confidence on a 0–1 scale, whatever scale the provider uses.
+    pub confidence: f32,
+}
+
+impl Word {
+    pub fn new(text: impl Into<String>, bbox: BoundingBox, confidence: f32) -> Self {
+        Self {
+            text: text.into(),
+            bbox,
+            confidence,
+        }
+    }
+
+    /// Words count towards aggregate confidence in proportion to their length.
+    fn weight(&self) -> usize {
+        self.text.chars().count()
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Line {
+    pub bbox: BoundingBox,
+    pub confidence: f32,
+    pub words: Vec<Word>,
+}
+
+impl Line {
+    /// Builds a line whose box and confidence are derived from its words.
+    pub fn from_words(words: Vec<Word>) -> Self {
+        Self {
+            bbox: BoundingBox::enclosing(words.iter().map(|w| &w.bbox)),
+            confidence: weighted_mean(words.iter().map(|w| (w.confidence, w.weight()))),
+            words,
+        }
+    }
+
+    fn weight(&self) -> usize {
+        self.words.iter().map(Word::weight).sum()
+    }
+
+    pub fn text(&self) -> String {
+        self.words
+            .iter()
+            .map(|w| w.text.as_str())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn filter_confidence(self, threshold: f32) -> Option<Line> {
+        if self.confidence < threshold {
+            return None;
+        }
+        let words: Vec<Word> = self
+            .words
+            .into_iter()
+            .filter(|word| word.confidence >= threshold)
+            .collect();
+        (!words.is_empty()).then(|| Line::from_words(words))
+    }
+
+    fn filter_words(self, f: &mut impl FnMut(Word) -> Option<Word>) -> Option<Line> {
+        let words: Vec<Word> = self.words.into_iter().filter_map(f).collect();
+        (!words.is_empty()).then(|| Line::from_words(words))
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Paragraph {
+    pub bbox: BoundingBox,
+    pub confidence: f32,
+    pub lines: Vec<Line>,
+}
+
+impl Paragraph {
+    pub fn from_lines(lines: Vec<Line>) -> Self {
+        Self {
+            bbox: BoundingBox::enclosing(lines.iter().map(|l| &l.bbox)),
+            confidence: weighted_mean(lines.iter().map(|l| (l.confidence, l.weight()))),
+            lines,
+        }
+    }
+
+    fn weight(&self) -> usize {
+        self.lines.iter().map(Line::weight).sum()
+    }
+
+    pub fn text(&self) -> String {
+        self.lines
+            .iter()
+            .map(Line::text)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn filter_confidence(self, threshold: f32) -> Option<Paragraph> {
+        let lines: Vec<Line> = self
+            .lines
+            .into_iter()
+            .filter_map(|line| line.filter_confidence(threshold))
+            .collect();
+        (!lines.is_empty()).then(|| Paragraph::from_lines(lines))
+    }
+
+    fn filter_words(self, f: &mut impl FnMut(Word) -> Option<Word>) -> Option<Paragraph> {
+        let lines: Vec<Line> = self
+            .lines
+            .into_iter()
+            .filter_map(|line| line.filter_words(f))
+            .collect();
+        (!lines.is_empty()).then(|| Paragraph::from_lines(lines))
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Block {
+    pub bbox: BoundingBox,
+    pub confidence: f32,
+    pub paragraphs: Vec<Paragraph>,
+}
+
+impl Block {
+    pub fn from_paragraphs(paragraphs: Vec<Paragraph>) -> Self {
+        Self {
+            bbox: BoundingBox::enclosing(paragraphs.iter().map(|p| &p.bbox)),
+            confidence: weighted_mean(paragraphs.iter().map(|p| (p.confidence, p.weight()))),
+            paragraphs,
+        }
+    }
+
+    pub fn text(&self) -> String {
+        self.paragraphs
+            .iter()
+            .map(Paragraph::text)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn filter_confidence(self, threshold: f32) -> Option<Block> {
+        let paragraphs: Vec<Paragraph> = self
+            .paragraphs
+            .into_iter()
+            .filter_map(|paragraph| paragraph.filter_confidence(threshold))
+            .collect();
+        (!paragraphs.is_empty()).then(|| Block::from_paragraphs(paragraphs))
+    }
+
+    fn filter_words(self, f: &mut impl FnMut(Word) -> Option<Word>) -> Option<Block> {
+        let paragraphs: Vec<Paragraph> = self
+            .paragraphs
+            .into_iter()
+            .filter_map(|paragraph| paragraph.filter_words(f))
+            .collect();
+        (!paragraphs.is_empty()).then(|| Block::from_paragraphs(paragraphs))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Page {
+    /// 1-based position of the page in its document, e.g. a PDF; 1 for a single image.
+    #[serde(default = "page_number_default")]
+    pub number: u32,
+    pub width: u32,
+    pub height: u32,
+    /// Clockwise rotation, in degrees, that preprocessing applied to make the page upright
+    /// (see [`crate::Preprocessor::orientation`] and [`crate::Preprocessor::deskew`]). Boxes
+    /// are still reported in the original, unrotated image.
+    #[serde(default)]
+    pub rotation: f64,
+    pub blocks: Vec<Block>,
+}
+
+impl Default for Page {
+    fn default() -> Self {
+        Self::new(0, 0, Vec::new())
+    }
+}
+
+impl Page {
+    pub fn new(width: u32, height: u32, blocks: Vec<Block>) -> Self {
+        Self {
+            number: 1,
+            width,
+            height,
+            rotation: 0.0,
+            blocks,
+        }
+    }
+
+    pub fn text(&self) -> String {
+        self.blocks
+            .iter()
+            .map(Block::text)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    pub fn words(&self) -> impl Iterator<Item = &Word> {
+        self.blocks
+            .iter()
+            .flat_map(|b| &b.paragraphs)
+            .flat_map(|p| &p.lines)
+            .flat_map(|l| &l.words)
+    }
+
+    /// Keeps the words `f` returns, dropping lines, paragraphs and blocks left empty.
+    pub(crate) fn filter_words(&mut self, mut f: impl FnMut(Word) -> Option<Word>) {
+        self.blocks = std::mem::take(&mut self.blocks)
+            .into_iter()
+            .filter_map(|block| block.filter_words(&mut f))
+            .collect();
+    }
+}
+
+/// Provider-independent recognition result: pages → blocks → paragraphs → lines → words.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OcrResult {
+    pub pages: Vec<Page>,
+    /// `false` when the provider reports no confidence and the word confidences were
+    /// synthesized (e.g. Windows OCR, which always yields `1.0`).
+    #[serde(default = "confidence_reported_default")]
+    pub confidence_reported: bool,
+    /// Name of the backend that produced the result, set by [`crate::OcrEngine`].
+    #[serde(default)]
+    pub provider: Option<String>,
+}
+
+impl Default for OcrResult {
+    fn default() -> Self {
+        Self::new(Vec::new())
+    }
+}
+
+impl OcrResult {
+    pub fn new(pages: Vec<Page>) -> Self {
+        Self {
+            pages,
+            confidence_reported: true,
+            provider: None,
+        }
+    }
+
+    /// Convenience constructor for providers that report a flat list of lines.
+    pub fn from_lines(width: u32, height: u32, lines: Vec<Line>) -> Self {
+        if lines.is_empty() {
+            return Self::new(vec![Page::new(width, height, Vec::new())]);
+        }
+        let block = Block::from_paragraphs(vec![Paragraph::from_lines(lines)]);
+        Self::new(vec![Page::new(width, height, vec![block])])
+    }
+
+    /// Plain text of the whole document, one line per recognized line.
+    pub fn text(&self) -> String {
+        self.pages
+            .iter()
+            .map(Page::text)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    pub fn words(&self) -> impl Iterator<Item = &Word> {
+        self.pages.iter().flat_map(Page::words)
+    }
+
+    /// Overall confidence on a 0–1 scale: the mean of word confidences weighted by word
+    /// length, so a confident long identifier outweighs a doubtful stray character.
+    /// `None` if nothing was recognized.
+    pub fn confidence(&self) -> Option<f64> {
+        let mut weight = 0usize;
+        let mut total = 0.0f64;
+        for word in self.words() {
+            total += word.confidence as f64 * word.weight() as f64;
+            weight += word.weight();
+        }
+        if weight > 0 {
+            Some(total / weight as f64)
+        } else {
+            None
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words().next().is_none()
+    }
+
+    /// Drops lines and words whose confidence is below `threshold` (0–1), together with any
+    /// paragraph or block left empty. Aggregates are recomputed from what remains.
+    pub fn filter_confidence(self, threshold: f32) -> Self {
+        if threshold <= 0.0 {
+            return self;
+        }
+        let pages = self
+            .pages
+            .into_iter()
+            .map(|page| Page {
+                blocks: page
+                    .blocks
+                    .into_iter()
+                    .filter_map(|block| block.filter_confidence(threshold))
+                    .collect(),
+                ..page
+            })
+            .collect();
+        Self { pages, ..self }
+    }
+
+    /// Replaces every non-empty box in the result with `f(box)`.
+    pub(crate) fn map_boxes(&mut self, mut f: impl FnMut(BoundingBox) -> BoundingBox) {
+        let mut map = |bbox: &mut BoundingBox| {
+            if !bbox.is_empty() {
+                *bbox = f(*bbox);
+            }
+        };
+        for block in self.pages.iter_mut().flat_map(|p| &mut p.blocks) {
+            map(&mut block.bbox);
+            for paragraph in &mut block.paragraphs {
+                map(&mut paragraph.bbox);
+                for line in &mut paragraph.lines {
+                    map(&mut line.bbox);
+                    for word in &mut line.words {
+                        map(&mut word.bbox);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Keeps only characters in `allowed` (all, when `None`) and not in `denied`.
+    ///
+    /// Other characters are replaced by a permitted look-alike where one exists (`O` → `0`,
+    /// `l` → `1`, ...) and dropped otherwise. Words left empty are removed.
+    pub fn restrict_characters(self, allowed: Option<&str>, denied: Option<&str>) -> Self {
+        if allowed.is_none() && denied.is_none() {
+            return self;
+        }
+        let filter = CharacterFilter { allowed, denied };
+        let mut restrict = |word: Word| {
+            let text = filter.apply(&word.text);
+            (!text.is_empty()).then_some(Word { text, ..word })
+        };
+        let pages = self
+            .pages
+            .into_iter()
+            .map(|page| Page {
+                blocks: page
+                    .blocks
+                    .into_iter()
+                    .filter_map(|block| block.filter_words(&mut restrict))
+                    .collect(),
+                ..page
+            })
+            .collect();
+        Self { pages, ..self }
+    }
+}
+
+fn confidence_reported_default() -> bool {
+    true
+}
+
+fn page_number_default() -> u32 {
+    1
+}
+
+fn weighted_mean(values: impl Iterator<Item = (f32, usize)>) -> f32 {
+    let (total, weight) = values.fold((0.0f32, 0usize), |(t, w), (value, weight)| {
+        (t + value * weight as f32, w + weight)
+    });
+    if weight > 0 {
+        total / weight as f32
+    } else {
+        0.0
+    }
+}
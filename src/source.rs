@@ -0,0 +1,187 @@
+use image::codecs::gif::GifDecoder;
+use image::codecs::png::PngDecoder;
+use image::{
+    AnimationDecoder, DynamicImage, GrayImage, ImageBuffer, ImageError, ImageFormat, ImageReader,
+    Luma, LumaA, Rgb, RgbImage, Rgba,
+};
+use std::fs::File;
+use std::io::{BufReader, Read, Seek};
+use std::path::{Path, PathBuf};
+use tiff::decoder::{Decoder as TiffDecoder, DecodingResult};
+use tiff::ColorType;
+
+use crate::error::{OcrError, Result};
+
+/// Something the engine can recognize: an image file on disk or an already decoded image.
+#[derive(Debug, Clone)]
+pub enum ImageSource {
+    Path(PathBuf),
+    Image(DynamicImage),
+}
+
+impl ImageSource {
+    /// Decodes the source. This does file I/O and image decoding, so call it off the async
+    /// runtime.
+    pub fn load(self) -> Result<DynamicImage> {
+        match self {
+            ImageSource::Path(path) => Ok(image::open(path)?),
+            ImageSource::Image(image) => Ok(image),
+        }
+    }
+
+    /// Decodes every frame: each page of a multi-page TIFF, each frame of an animated GIF or
+    /// PNG, or else the single image. Like [`ImageSource::load`], call it off the async
+    /// runtime.
+    pub fn load_frames(self) -> Result<Vec<DynamicImage>> {
+        let path = match self {
+            ImageSource::Path(path) => path,
+            ImageSource::Image(image) => return Ok(vec![image]),
+        };
+        let open = || {
+            File::open(&path)
+                .map(BufReader::new)
+                .map_err(ImageError::IoError)
+        };
+        let reader = ImageReader::new(open()?).with_guessed_format()?;
+        match reader.format() {
+            Some(ImageFormat::Tiff) => tiff_pages(open()?),
+            Some(ImageFormat::Gif) => animation_frames(GifDecoder::new(open()?)?),
+            Some(ImageFormat::Png) => {
+                let decoder = PngDecoder::new(open()?)?;
+                if decoder.is_apng()? {
+                    animation_frames(decoder.apng()?)
+                } else {
+                    Ok(vec![DynamicImage::from_decoder(decoder)?])
+                }
+            }
+            _ => Ok(vec![reader.decode()?]),
+        }
+    }
+}
+
+/// Every frame of an animation, composited to full frames.
+fn animation_frames<'a>(decoder: impl AnimationDecoder<'a>) -> Result<Vec<DynamicImage>> {
+    Ok(decoder
+        .into_frames()
+        .collect_frames()?
+        .into_iter()
+        .map(|frame| DynamicImage::ImageRgba8(frame.into_buffer()))
+        .collect())
+}
+
+fn tiff_error(err: tiff::TiffError) -> OcrError {
+    OcrError::InvalidImage(format!("TIFF: {}", err))
+}
+
+/// Every page of a TIFF, in order.
+fn tiff_pages(reader: impl Read + Seek) -> Result<Vec<DynamicImage>> {
+    let mut decoder = TiffDecoder::new(reader).map_err(tiff_error)?;
+    let mut pages = Vec::new();
+    loop {
+        pages.push(tiff_page(&mut decoder)?);
+        if !decoder.more_images() {
+            return Ok(pages);
+        }
+        decoder.next_image().map_err(tiff_error)?;
+    }
+}
+
+/// The current page of a TIFF, in the color types fax and scanner archives use.
+fn tiff_page<R: Read + Seek>(decoder: &mut TiffDecoder<R>) -> Result<DynamicImage> {
+    let (width, height) = decoder.dimensions().map_err(tiff_error)?;
+    let color = decoder.colortype().map_err(tiff_error)?;
+    let data = decoder.read_image().map_err(tiff_error)?;
+    let size_mismatch = || OcrError::InvalidImage("TIFF: page data does not match its size".into());
+    let image = match (color, data) {
+        // Bilevel fax pages: packed rows, already with 1 as white
+        (ColorType::Gray(1), DecodingResult::U8(bits)) => {
+            let row_bytes = width.div_ceil(8) as usize;
+            if bits.len() < row_bytes * height as usize {
+                return Err(size_mismatch());
+            }
+            DynamicImage::ImageLuma8(GrayImage::from_fn(width, height, |x, y| {
+                let byte = bits[y as usize * row_bytes + x as usize / 8];
+                Luma([if (byte >> (7 - x % 8)) & 1 == 1 {
+                    255
+                } else {
+                    0
+                }])
+            }))
+        }
+        (ColorType::Gray(8), DecodingResult::U8(v)) => DynamicImage::ImageLuma8(
+            ImageBuffer::from_raw(width, height, v).ok_or_else(size_mismatch)?,
+        ),
+        (ColorType::Gray(16), DecodingResult::U16(v)) => DynamicImage::ImageLuma16(
+            ImageBuffer::<Luma<u16>, _>::from_raw(width, height, v).ok_or_else(size_mismatch)?,
+        ),
+        (ColorType::GrayA(8), DecodingResult::U8(v)) => DynamicImage::ImageLumaA8(
+            ImageBuffer::<LumaA<u8>, _>::from_raw(width, height, v).ok_or_else(size_mismatch)?,
+        ),
+        (ColorType::RGB(8), DecodingResult::U8(v)) => DynamicImage::ImageRgb8(
+            ImageBuffer::from_raw(width, height, v).ok_or_else(size_mismatch)?,
+        ),
+        (ColorType::RGB(16), DecodingResult::U16(v)) => DynamicImage::ImageRgb16(
+            ImageBuffer::<Rgb<u16>, _>::from_raw(width, height, v).ok_or_else(size_mismatch)?,
+        ),
+        (ColorType::RGBA(8), DecodingResult::U8(v)) => DynamicImage::ImageRgba8(
+            ImageBuffer::<Rgba<u8>, _>::from_raw(width, height, v).ok_or_else(size_mismatch)?,
+        ),
+        (ColorType::RGBA(16), DecodingResult::U16(v)) => DynamicImage::ImageRgba16(
+            ImageBuffer::<Rgba<u16>, _>::from_raw(width, height, v).ok_or_else(size_mismatch)?,
+        ),
+        (ColorType::CMYK(8), DecodingResult::U8(v)) => {
+            if v.len() < 4 * width as usize * height as usize {
+                return Err(size_mismatch());
+            }
+            let rgb = v
+                .chunks_exact(4)
+                .flat_map(|cmyk| {
+                    let k = 255 - cmyk[3] as u32;
+                    cmyk[..3]
+                        .iter()
+                        .map(move |&c| ((255 - c as u32) * k / 255) as u8)
+                })
+                .collect();
+            DynamicImage::ImageRgb8(
+                RgbImage::from_raw(width, height, rgb).ok_or_else(size_mismatch)?,
+            )
+        }
+        (color, _) => {
+            return Err(OcrError::InvalidImage(format!(
+                "TIFF: unsupported color type {:?}",
+                color
+            )))
+        }
+    };
+    Ok(image)
+}
+
+impl From<DynamicImage> for ImageSource {
+    fn from(image: DynamicImage) -> Self {
+        ImageSource::Image(image)
+    }
+}
+
+impl From<PathBuf> for ImageSource {
+    fn from(path: PathBuf) -> Self {
+        ImageSource::Path(path)
+    }
+}
+
+impl From<&Path> for ImageSource {
+    fn from(path: &Path) -> Self {
+        ImageSource::Path(path.to_path_buf())
+    }
+}
+
+impl From<&str> for ImageSource {
+    fn from(path: &str) -> Self {
+        ImageSource::Path(PathBuf::from(path))
+    }
+}
+
+impl From<String> for ImageSource {
+    fn from(path: String) -> Self {
+        ImageSource::Path(PathBuf::from(path))
+    }
+}
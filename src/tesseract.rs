@@ -1,114 +1,589 @@
-use image::DynamicImage;
-use rusty_tesseract::{Args, DataOutput, Image};
-use std::collections::HashMap;
-
-use crate::Language;
-
-pub fn perform_ocr_tesseract(
-    image: &DynamicImage,
-    languages: Vec<Language>,
-) -> (String, String, Option<f64>) {
-    let language_string = if languages.is_empty() {
-        "eng".to_string()
-    } else {
-        languages
-            .iter()
-            .filter_map(|lang| lang.as_tesseract_code().map(|code| code.to_string()))
-            .collect::<Vec<String>>()
-            .join("+")
-    };
-    let args = Args {
-        lang: language_string,
-        config_variables: HashMap::from([("tessedit_create_tsv".into(), "1".into())]),
-        dpi: Some(600), // 150 is a balanced option, 600 seems faster surprisingly, the bigger the number the more granualar result
-        psm: Some(1), // PSM 1: Automatic page segmentation with OSD. PSM 3: Automatic page segmentation with OSD
-        oem: Some(1), //1: Neural nets LSTM engine only,    3: Default, based on what is available. (Default)
-    };
+use async_trait::async_trait;
+use image::{DynamicImage, GenericImageView};
+use std::collections::{BTreeMap, HashMap};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::{Mutex, MutexGuard, OnceLock, PoisonError};
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+use crate::backend::{cancelled, run_blocking, Capabilities, OcrBackend, ProviderStatus};
+use crate::error::{OcrError, Result};
+use crate::result::{Block, BoundingBox, Line, OcrResult, Page, Paragraph, Word};
+use crate::{Language, OcrOptions};
 
-    let ocr_image = Image::from_dynamic_image(image).unwrap();
+/// Tesseract TSV level of a word row.
+const WORD_LEVEL: i32 = 5;
 
-    // Extract data output
-    let data_output = rusty_tesseract::image_to_data(&ocr_image, &args).unwrap();
-    // let tsv_output = data_output_to_tsv(&data_output);
+const TESSERACT_BINARY: &str = "tesseract";
 
-    // Extract text from data output
-    let text = data_output_to_text(&data_output);
-    let json_output = data_output_to_json(&data_output);
+/// How often a running Tesseract process is polled for completion.
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
 
-    let overall_confidence = calculate_overall_confidence(&data_output);
+/// `--version` and `--list-langs` answer at once; this only guards against a hung process.
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(10);
 
-    (text, json_output, Some(overall_confidence))
+/// Traineddata codes found by the last successful `--list-langs`, per tessdata directory.
+/// `None` is Tesseract's own default directory.
+static INSTALLED_LANGUAGES: OnceLock<Mutex<HashMap<Option<PathBuf>, Vec<String>>>> =
+    OnceLock::new();
+
+fn installed_languages_cache() -> MutexGuard<'static, HashMap<Option<PathBuf>, Vec<String>>> {
+    // The map is only ever replaced entry by entry, so a panic elsewhere cannot corrupt it
+    INSTALLED_LANGUAGES
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
 }
 
-fn data_output_to_text(data_output: &DataOutput) -> String {
-    let mut text = String::new();
-    for record in &data_output.data {
-        if !record.text.is_empty() {
-            if !text.is_empty() {
-                text.push(' ');
-            }
-            text.push_str(&record.text);
+/// Tesseract-specific settings, attached with [`OcrOptions::tesseract`].
+///
+/// Ignored by every other provider.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TesseractOptions {
+    /// Page segmentation mode (`--psm`), e.g. 6 for a single uniform block of text.
+    pub psm: u8,
+    /// OCR engine mode (`--oem`), e.g. 1 for the LSTM engine only.
+    pub oem: u8,
+    pub dpi: u32,
+    /// Config variables passed as `-c name=value`.
+    pub config_variables: BTreeMap<String, String>,
+    /// Directory holding the traineddata files, instead of Tesseract's default.
+    pub tessdata_dir: Option<PathBuf>,
+    /// Word list biasing recognition (`--user-words`).
+    pub user_words: Option<PathBuf>,
+    /// Pattern list biasing recognition (`--user-patterns`).
+    pub user_patterns: Option<PathBuf>,
+}
+
+impl Default for TesseractOptions {
+    fn default() -> Self {
+        Self {
+            psm: 1,   // automatic page segmentation with OSD
+            oem: 1,   // neural nets LSTM engine only
+            dpi: 600, // 150 is a balanced option, 600 seems faster surprisingly, the bigger the number the more granular result
+            config_variables: BTreeMap::new(),
+            tessdata_dir: None,
+            user_words: None,
+            user_patterns: None,
         }
     }
-    text
-}
-
-fn data_output_to_json(data_output: &DataOutput) -> String {
-    let mut lines: Vec<HashMap<String, String>> = Vec::new();
-    let mut current_line = String::new();
-    let mut current_conf = 0.0;
-    let mut word_count = 0;
-    let mut last_word_num = 0;
-
-    for record in &data_output.data {
-        if record.word_num == 0 && !current_line.is_empty() {
-            let avg_conf = current_conf / word_count as f32;
-            let mut line_data = HashMap::new();
-            line_data.insert("text".to_string(), current_line.clone());
-            line_data.insert("confidence".to_string(), format!("{:.2}", avg_conf));
-            line_data.insert(
-                "line_position".to_string(),
-                format!(
-                    "level{}page_num{}block_num{}par_num{}line_num{}",
-                    record.level,
-                    record.page_num,
-                    record.block_num,
-                    record.par_num,
-                    record.line_num
-                ),
-            );
-            lines.push(line_data);
-            current_line.clear();
-            current_conf = 0.0;
-            word_count = 0;
+}
+
+impl TesseractOptions {
+    pub fn psm(mut self, psm: u8) -> Self {
+        self.psm = psm;
+        self
+    }
+
+    pub fn oem(mut self, oem: u8) -> Self {
+        self.oem = oem;
+        self
+    }
+
+    pub fn dpi(mut self, dpi: u32) -> Self {
+        self.dpi = dpi;
+        self
+    }
+
+    /// Sets a config variable, e.g. `("preserve_interword_spaces", "1")`.
+    pub fn config_variable(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.config_variables.insert(name.into(), value.into());
+        self
+    }
+
+    pub fn tessdata_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.tessdata_dir = Some(dir.into());
+        self
+    }
+
+    pub fn user_words(mut self, path: impl Into<PathBuf>) -> Self {
+        self.user_words = Some(path.into());
+        self
+    }
+
+    pub fn user_patterns(mut self, path: impl Into<PathBuf>) -> Self {
+        self.user_patterns = Some(path.into());
+        self
+    }
+}
+
+/// Backend running the local `tesseract` executable on the blocking pool.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TesseractBackend;
+
+#[async_trait]
+impl OcrBackend for TesseractBackend {
+    fn name(&self) -> &str {
+        "tesseract"
+    }
+
+    /// Languages with an installed traineddata file.
+    fn supported_languages(&self) -> Vec<Language> {
+        installed_languages().unwrap_or_default()
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            word_boxes: true,
+            confidence: true,
+            blocking: true,
         }
-        if record.word_num > last_word_num {
-            if !current_line.is_empty() {
-                current_line.push(' ');
+    }
+
+    async fn probe(&self) -> ProviderStatus {
+        let name = self.name().to_string();
+        tokio::task::spawn_blocking(move || {
+            let version = match tesseract_version() {
+                Ok(version) => version,
+                Err(err) => return ProviderStatus::unavailable(name, err.to_string()),
+            };
+            let codes = match tesseract_languages() {
+                Ok(codes) => codes,
+                Err(err) => return ProviderStatus::unavailable(name, err.to_string()),
+            };
+            let mut status = ProviderStatus::available(
+                name,
+                codes
+                    .iter()
+                    .filter_map(|code| Language::from_tesseract_code(code))
+                    .collect(),
+            );
+            status.version = Some(version);
+            if status.languages.is_empty() {
+                status.available = false;
+                status.detail = Some("no traineddata files installed".to_string());
             }
-            current_line.push_str(&record.text);
-            current_conf += record.conf;
-            word_count += 1;
+            status
+        })
+        .await
+        .unwrap_or_else(|e| ProviderStatus::unavailable("tesseract", e.to_string()))
+    }
+
+    async fn recognize(&self, image: &DynamicImage, options: &OcrOptions) -> Result<OcrResult> {
+        let (image, options) = (image.clone(), options.clone());
+        run_blocking(move || perform_ocr_tesseract(&image, &options)).await
+    }
+}
+
+/// Version reported by `tesseract --version`, e.g. `"5.3.0"`.
+pub fn tesseract_version() -> Result<String> {
+    let output = run_tesseract_command(&["--version"], COMMAND_TIMEOUT)?;
+    // Older releases print the banner on stderr, newer ones on stdout
+    output
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("tesseract "))
+        .map(|version| version.trim().to_string())
+        .ok_or_else(|| {
+            OcrError::Decode(format!("unexpected tesseract version output: {:?}", output))
+        })
+}
+
+/// Traineddata codes reported by `tesseract --list-langs`, e.g. `["eng", "osd"]`.
+pub fn tesseract_languages() -> Result<Vec<String>> {
+    list_languages(None, COMMAND_TIMEOUT)
+}
+
+fn list_languages(tessdata_dir: Option<&Path>, timeout: Duration) -> Result<Vec<String>> {
+    let output = match tessdata_dir {
+        Some(dir) => run_tesseract_command(
+            &["--tessdata-dir", &dir.to_string_lossy(), "--list-langs"],
+            timeout,
+        )?,
+        None => run_tesseract_command(&["--list-langs"], timeout)?,
+    };
+    Ok(output
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with("List of available languages"))
+        .map(str::to_string)
+        .collect())
+}
+
+/// Installed languages that map to a [`Language`], like `tesseract --list-langs`.
+///
+/// The listing is cached once it succeeds. Recognition lists again before rejecting a
+/// language missing from it, so traineddata installed later is picked up.
+pub fn installed_languages() -> Result<Vec<Language>> {
+    installed_languages_in(None)
+}
+
+/// Like [`installed_languages`], for a tessdata directory other than Tesseract's default.
+pub fn installed_languages_in(tessdata_dir: Option<&Path>) -> Result<Vec<Language>> {
+    Ok(
+        installed_language_codes(tessdata_dir, COMMAND_TIMEOUT, false)?
+            .iter()
+            .filter_map(|code| Language::from_tesseract_code(code))
+            .collect(),
+    )
+}
+
+/// Cached traineddata codes of `tessdata_dir`, listed anew when missing or on `refresh`.
+fn installed_language_codes(
+    tessdata_dir: Option<&Path>,
+    timeout: Duration,
+    refresh: bool,
+) -> Result<Vec<String>> {
+    let key = tessdata_dir.map(Path::to_path_buf);
+    if !refresh {
+        if let Some(codes) = installed_languages_cache().get(&key) {
+            return Ok(codes.clone());
         }
-        last_word_num = record.word_num;
     }
-    if !current_line.is_empty() {
-        let avg_conf = current_conf / word_count as f32;
-        let mut line_data = HashMap::new();
-        line_data.insert("text".to_string(), current_line);
-        line_data.insert("confidence".to_string(), format!("{:.2}", avg_conf));
-        lines.push(line_data);
+    // Listed without holding the lock; a concurrent miss just lists twice
+    let codes = list_languages(tessdata_dir, timeout)?;
+    installed_languages_cache().insert(key, codes.clone());
+    Ok(codes)
+}
+
+/// Builds the `-l` argument, failing before Tesseract starts if a requested language has no
+/// installed traineddata. Languages Tesseract has no model for are skipped with a warning.
+fn language_argument(
+    languages: &[Language],
+    tessdata_dir: Option<&Path>,
+    timeout: Duration,
+) -> Result<String> {
+    if languages.is_empty() {
+        return Ok("eng".to_string());
     }
 
-    serde_json::to_string_pretty(&lines).unwrap()
+    let mut installed = installed_language_codes(tessdata_dir, timeout, false)?;
+    let listed = |installed: &[String], code: &str| installed.iter().any(|i| i == code);
+    if languages
+        .iter()
+        .filter_map(Language::as_tesseract_code)
+        .any(|code| !listed(&installed, code))
+    {
+        // The cached listing may predate the traineddata being installed
+        installed = installed_language_codes(tessdata_dir, timeout, true)?;
+    }
+    let mut codes = Vec::with_capacity(languages.len());
+    let mut missing = Vec::new();
+    for lang in languages {
+        match lang.as_tesseract_code() {
+            Some(code) if listed(&installed, code) => codes.push(code),
+            Some(code) => missing.push(code),
+            None => warn!("tesseract has no model for {:?}, skipping it", lang),
+        }
+    }
+
+    if !missing.is_empty() {
+        return Err(OcrError::LanguageNotInstalled(format!(
+            "no tesseract traineddata for {}",
+            missing.join(", ")
+        )));
+    }
+    if codes.is_empty() {
+        return Err(OcrError::LanguageNotInstalled(format!(
+            "tesseract supports none of {:?}",
+            languages
+        )));
+    }
+    Ok(codes.join("+"))
 }
 
-fn calculate_overall_confidence(data_output: &DataOutput) -> f64 {
-    let total_conf: f32 = data_output.data.iter().map(|record| record.conf).sum();
-    let count = data_output.data.len();
-    if count > 0 {
-        (total_conf / count as f32) as f64
-    } else {
-        0.0
+/// Runs a short informational tesseract command and returns stdout followed by stderr.
+///
+/// The process is killed and [`OcrError::Timeout`] returned if it runs longer than `timeout`.
+fn run_tesseract_command(args: &[&str], timeout: Duration) -> Result<String> {
+    // Files rather than pipes, so that a chatty process cannot block on a full pipe
+    let mut stdout = tempfile::tempfile()?;
+    let mut stderr = tempfile::tempfile()?;
+    let mut child = Command::new(TESSERACT_BINARY)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(stdout.try_clone()?)
+        .stderr(stderr.try_clone()?)
+        .spawn()
+        .map_err(spawn_error)?;
+    let status = wait_with_timeout(&mut child, timeout)?;
+    if !status.success() {
+        return Err(OcrError::Backend(format!(
+            "tesseract {} exited with {}",
+            args.join(" "),
+            status
+        )));
     }
+
+    let mut output = Vec::new();
+    for file in [&mut stdout, &mut stderr] {
+        file.seek(SeekFrom::Start(0))?;
+        file.read_to_end(&mut output)?;
+    }
+    Ok(String::from_utf8_lossy(&output).into_owned())
+}
+
+fn spawn_error(err: std::io::Error) -> OcrError {
+    match err.kind() {
+        std::io::ErrorKind::NotFound => {
+            OcrError::ProviderUnavailable("tesseract executable not found".to_string())
+        }
+        _ => OcrError::from(err),
+    }
+}
+
+/// Runs the `tesseract` executable on `image`.
+///
+/// The process is killed and [`OcrError::Timeout`] returned if it runs longer than the
+/// options' timeout.
+pub fn perform_ocr_tesseract(image: &DynamicImage, options: &OcrOptions) -> Result<OcrResult> {
+    // Listing the installed languages counts against the timeout too
+    let deadline = Instant::now() + options.timeout;
+    let tesseract = &options.tesseract;
+    let language_string = language_argument(
+        &options.languages,
+        tesseract.tessdata_dir.as_deref(),
+        options.timeout,
+    )?;
+
+    let workdir = tempfile::tempdir()?;
+    let input_path = workdir.path().join("input.png");
+    let output_base = workdir.path().join("output");
+    image
+        .to_rgba8()
+        .save_with_format(&input_path, image::ImageFormat::Png)?;
+
+    let mut command = Command::new(TESSERACT_BINARY);
+    command
+        .arg(&input_path)
+        .arg(&output_base)
+        .args(["-l", &language_string])
+        .args(["--dpi", &tesseract.dpi.to_string()])
+        .args(["--psm", &tesseract.psm.to_string()])
+        .args(["--oem", &tesseract.oem.to_string()]);
+    if let Some(dir) = &tesseract.tessdata_dir {
+        command.arg("--tessdata-dir").arg(dir);
+    }
+    if let Some(path) = &tesseract.user_words {
+        command.arg("--user-words").arg(path);
+    }
+    if let Some(path) = &tesseract.user_patterns {
+        command.arg("--user-patterns").arg(path);
+    }
+    // Restricting recognition itself beats remapping the result afterwards
+    if let Some(allowed) = &options.allowed_characters {
+        command
+            .arg("-c")
+            .arg(format!("tessedit_char_whitelist={}", allowed));
+    }
+    if let Some(denied) = &options.denied_characters {
+        command
+            .arg("-c")
+            .arg(format!("tessedit_char_blacklist={}", denied));
+    }
+    for (name, value) in &tesseract.config_variables {
+        command.arg("-c").arg(format!("{}={}", name, value));
+    }
+    command
+        .arg("tsv")
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(std::fs::File::create(workdir.path().join("stderr.txt"))?);
+
+    let mut child = command.spawn().map_err(spawn_error)?;
+
+    let status = wait_until(&mut child, deadline, options.timeout)?;
+    if !status.success() {
+        let stderr = std::fs::read_to_string(workdir.path().join("stderr.txt")).unwrap_or_default();
+        return Err(exit_error(status, &stderr));
+    }
+
+    let tsv = std::fs::read_to_string(output_base.with_extension("tsv"))?;
+    let rows = parse_tsv(&tsv)?;
+
+    let (width, height) = image.dimensions();
+    Ok(rows_to_result(&rows, width, height))
+}
+
+/// Clockwise rotation, in degrees (0, 90, 180 or 270), that makes `image` upright according
+/// to Tesseract's orientation and script detection.
+///
+/// Needs the `osd` traineddata, looked up in [`TesseractOptions::tessdata_dir`]. Fails when
+/// the image has too little text to tell, or with [`OcrError::Timeout`] after
+/// [`OcrOptions::timeout`].
+pub fn detect_orientation(image: &DynamicImage, options: &OcrOptions) -> Result<u32> {
+    let workdir = tempfile::tempdir()?;
+    let input_path = workdir.path().join("input.png");
+    let output_base = workdir.path().join("output");
+    image
+        .to_rgba8()
+        .save_with_format(&input_path, image::ImageFormat::Png)?;
+
+    let mut command = Command::new(TESSERACT_BINARY);
+    command
+        .arg(&input_path)
+        .arg(&output_base)
+        .args(["--psm", "0"]); // PSM 0: orientation and script detection only
+    if let Some(dir) = &options.tesseract.tessdata_dir {
+        command.arg("--tessdata-dir").arg(dir);
+    }
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(std::fs::File::create(workdir.path().join("stderr.txt"))?)
+        .spawn()
+        .map_err(spawn_error)?;
+
+    let status = wait_with_timeout(&mut child, options.timeout)?;
+    let stderr = std::fs::read_to_string(workdir.path().join("stderr.txt")).unwrap_or_default();
+    if !status.success() {
+        return Err(exit_error(status, &stderr));
+    }
+
+    // Nothing is written when there are too few characters
+    let osd = std::fs::read_to_string(output_base.with_extension("osd")).map_err(|_| {
+        OcrError::Backend(format!(
+            "tesseract could not detect the orientation: {}",
+            stderr.trim()
+        ))
+    })?;
+    osd.lines()
+        .find_map(|line| line.strip_prefix("Rotate:"))
+        .and_then(|degrees| degrees.trim().parse().ok())
+        .ok_or_else(|| OcrError::Decode(format!("unexpected tesseract OSD output: {:?}", osd)))
+}
+
+/// Waits for `child`, killing it when `timeout` expires or the caller stops waiting.
+fn wait_with_timeout(child: &mut Child, timeout: Duration) -> Result<ExitStatus> {
+    wait_until(child, Instant::now() + timeout, timeout)
+}
+
+/// Like [`wait_with_timeout`], for a `timeout` that started counting before the process did
+/// and expires at `deadline`.
+fn wait_until(child: &mut Child, deadline: Instant, timeout: Duration) -> Result<ExitStatus> {
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(status);
+        }
+        if cancelled() {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(OcrError::Backend("tesseract was cancelled".to_string()));
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(OcrError::Timeout(timeout));
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
+fn exit_error(status: ExitStatus, stderr: &str) -> OcrError {
+    // Tesseract reports missing traineddata on stderr and exits non-zero
+    if let Some(line) = stderr
+        .lines()
+        .find(|line| line.contains("Failed loading language"))
+    {
+        return OcrError::LanguageNotInstalled(line.trim().to_string());
+    }
+    OcrError::Backend(format!(
+        "tesseract exited with {}: {}",
+        status,
+        stderr.trim()
+    ))
+}
+
+/// One row of Tesseract's TSV output.
+#[derive(Debug, Clone, PartialEq)]
+struct TsvRow {
+    level: i32,
+    block_num: i32,
+    par_num: i32,
+    line_num: i32,
+    left: i32,
+    top: i32,
+    width: i32,
+    height: i32,
+    conf: f32,
+    text: String,
+}
+
+fn parse_tsv(tsv: &str) -> Result<Vec<TsvRow>> {
+    let mut rows = Vec::new();
+    // The first line is the column header
+    for line in tsv.lines().skip(1).filter(|line| !line.is_empty()) {
+        let columns: Vec<&str> = line.splitn(12, '\t').collect();
+        if columns.len() < 11 {
+            return Err(OcrError::Decode(format!(
+                "malformed tesseract row: {:?}",
+                line
+            )));
+        }
+        let int = |i: usize| -> Result<i32> {
+            columns[i]
+                .trim()
+                .parse()
+                .map_err(|_| OcrError::Decode(format!("malformed tesseract row: {:?}", line)))
+        };
+        rows.push(TsvRow {
+            level: int(0)?,
+            block_num: int(2)?,
+            par_num: int(3)?,
+            line_num: int(4)?,
+            left: int(6)?,
+            top: int(7)?,
+            width: int(8)?,
+            height: int(9)?,
+            conf: columns[10]
+                .trim()
+                .parse()
+                .map_err(|_| OcrError::Decode(format!("malformed tesseract row: {:?}", line)))?,
+            text: columns.get(11).map(|t| t.to_string()).unwrap_or_default(),
+        });
+    }
+    Ok(rows)
+}
+
+/// Rebuilds the page/block/paragraph/line hierarchy from Tesseract's flat TSV rows.
+fn rows_to_result(rows: &[TsvRow], width: u32, height: u32) -> OcrResult {
+    let mut blocks: Vec<Block> = Vec::new();
+    let mut paragraphs: Vec<Paragraph> = Vec::new();
+    let mut lines: Vec<Line> = Vec::new();
+    let mut words: Vec<Word> = Vec::new();
+    let mut current: Option<(i32, i32, i32)> = None;
+
+    for record in rows {
+        if record.level != WORD_LEVEL || record.text.trim().is_empty() {
+            continue;
+        }
+        let key = (record.block_num, record.par_num, record.line_num);
+        if let Some((block_num, par_num, line_num)) = current {
+            if line_num != key.2 || par_num != key.1 || block_num != key.0 {
+                lines.push(Line::from_words(std::mem::take(&mut words)));
+            }
+            if par_num != key.1 || block_num != key.0 {
+                paragraphs.push(Paragraph::from_lines(std::mem::take(&mut lines)));
+            }
+            if block_num != key.0 {
+                blocks.push(Block::from_paragraphs(std::mem::take(&mut paragraphs)));
+            }
+        }
+        current = Some(key);
+
+        words.push(Word::new(
+            record.text.trim(),
+            BoundingBox::new(
+                record.left as f64,
+                record.top as f64,
+                record.width as f64,
+                record.height as f64,
+            ),
+            // Tesseract reports 0–100
+            (record.conf / 100.0).clamp(0.0, 1.0),
+        ));
+    }
+
+    if !words.is_empty() {
+        lines.push(Line::from_words(words));
+    }
+    if !lines.is_empty() {
+        paragraphs.push(Paragraph::from_lines(lines));
+    }
+    if !paragraphs.is_empty() {
+        blocks.push(Block::from_paragraphs(paragraphs));
+    }
+
+    OcrResult::new(vec![Page::new(width, height, blocks)])
 }
@@ -0,0 +1,239 @@
+use crate::region::Rect;
+use crate::result::{Block, BoundingBox, Line, OcrResult, Page, Paragraph, Word};
+
+/// Splitting of large images into overlapping tiles, set with [`crate::OcrOptions::tiling`].
+///
+/// Images larger than `tile_size` on either side are cut into tiles recognized in parallel.
+/// Words recognized twice in an overlap are kept once, and lines cut by a tile edge are
+/// joined again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tiling {
+    /// Largest tile side, in pixels.
+    pub tile_size: u32,
+    /// Pixels shared by neighbouring tiles, at most half of `tile_size`. Words shorter than
+    /// half of this are never lost at a tile edge.
+    pub overlap: u32,
+}
+
+impl Default for Tiling {
+    fn default() -> Self {
+        Self {
+            tile_size: 2048,
+            overlap: 256,
+        }
+    }
+}
+
+impl Tiling {
+    /// Also shrinks the overlap to half of the new size if it was larger.
+    pub fn tile_size(mut self, pixels: u32) -> Self {
+        self.tile_size = pixels;
+        self.overlap = self.overlap.min(pixels / 2);
+        self
+    }
+
+    /// Clamped to half of the tile size.
+    pub fn overlap(mut self, pixels: u32) -> Self {
+        self.overlap = pixels.min(self.tile_size / 2);
+        self
+    }
+
+    /// Tiles covering a `width` × `height` image, row by row. A single tile when the image
+    /// fits in one.
+    pub(crate) fn tiles(&self, width: u32, height: u32) -> Vec<Tile> {
+        let xs = self.spans(width);
+        let ys = self.spans(height);
+        let (x_cores, y_cores) = (cores(&xs, width), cores(&ys, height));
+        let mut tiles = Vec::with_capacity(xs.len() * ys.len());
+        for (&(y, tile_height), &(top, bottom)) in ys.iter().zip(&y_cores) {
+            for (&(x, tile_width), &(left, right)) in xs.iter().zip(&x_cores) {
+                tiles.push(Tile {
+                    rect: Rect::new(x, y, tile_width, tile_height),
+                    core: BoundingBox::new(
+                        left as f64,
+                        top as f64,
+                        (right - left) as f64,
+                        (bottom - top) as f64,
+                    ),
+                });
+            }
+        }
+        tiles
+    }
+
+    /// Start and length of each tile along one side of `length` pixels.
+    fn spans(&self, length: u32) -> Vec<(u32, u32)> {
+        let size = self.tile_size.max(1);
+        if length <= size {
+            return vec![(0, length)];
+        }
+        // An overlap close to the tile size would make the stride, and so the tile count, blow up
+        let stride = size - self.overlap.min(size / 2);
+        let mut spans = Vec::new();
+        let mut start = 0;
+        loop {
+            if start + size >= length {
+                // Last tile flush with the edge, so it is full-size
+                spans.push((length - size, size));
+                break;
+            }
+            spans.push((start, size));
+            start += stride;
+        }
+        spans
+    }
+}
+
+/// The part of each span whose words it keeps: overlaps are split down the middle.
+fn cores(spans: &[(u32, u32)], length: u32) -> Vec<(u32, u32)> {
+    let boundaries: Vec<u32> = spans
+        .windows(2)
+        .map(|pair| (pair[1].0 + pair[0].0 + pair[0].1) / 2)
+        .collect();
+    (0..spans.len())
+        .map(|i| {
+            let start = if i == 0 { 0 } else { boundaries[i - 1] };
+            let end = boundaries.get(i).copied().unwrap_or(length);
+            (start, end)
+        })
+        .collect()
+}
+
+/// One tile and the part of the image its words are kept from.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Tile {
+    pub rect: Rect,
+    core: BoundingBox,
+}
+
+impl Tile {
+    fn owns(&self, word: &Word) -> bool {
+        let (x, y) = (
+            word.bbox.x + word.bbox.width / 2.0,
+            word.bbox.y + word.bbox.height / 2.0,
+        );
+        x >= self.core.x && x < self.core.right() && y >= self.core.y && y < self.core.bottom()
+    }
+}
+
+/// Lines of one tile, in image coordinates, keeping their block and paragraph.
+struct TileLine {
+    tile: usize,
+    block: usize,
+    paragraph: usize,
+    line: Line,
+}
+
+/// Stitches per-tile results, in tile order, into one result for the whole image.
+pub(crate) fn merge(width: u32, height: u32, tiles: Vec<(Tile, OcrResult)>) -> OcrResult {
+    let confidence_reported = tiles.iter().all(|(_, r)| r.confidence_reported);
+    let provider = tiles.iter().find_map(|(_, r)| r.provider.clone());
+
+    // Move every word into image coordinates and keep it only in the tile owning it
+    let mut lines: Vec<TileLine> = Vec::new();
+    let mut block_offset = 0;
+    for (index, (tile, mut result)) in tiles.into_iter().enumerate() {
+        let (dx, dy) = (tile.rect.x as f64, tile.rect.y as f64);
+        result.map_boxes(|b| BoundingBox::new(b.x + dx, b.y + dy, b.width, b.height));
+        let blocks = result.pages.into_iter().flat_map(|page| page.blocks);
+        let mut block_count = 0;
+        for (block, b) in blocks.enumerate() {
+            block_count += 1;
+            for (paragraph, p) in b.paragraphs.into_iter().enumerate() {
+                for line in p.lines {
+                    // Words without a box cannot be placed; keep them
+                    let words: Vec<Word> = line
+                        .words
+                        .into_iter()
+                        .filter(|w| w.bbox.is_empty() || tile.owns(w))
+                        .collect();
+                    if !words.is_empty() {
+                        lines.push(TileLine {
+                            tile: index,
+                            block: block_offset + block,
+                            paragraph,
+                            line: Line::from_words(words),
+                        });
+                    }
+                }
+            }
+        }
+        block_offset += block_count;
+    }
+
+    // Join lines a tile edge cut in two
+    for i in 0..lines.len() {
+        if lines[i].line.words.is_empty() {
+            continue;
+        }
+        let target = (0..i).find(|&j| {
+            lines[j].tile != lines[i].tile
+                && !lines[j].line.words.is_empty()
+                && same_row(&lines[j].line.bbox, &lines[i].line.bbox)
+        });
+        if let Some(j) = target {
+            let words = std::mem::take(&mut lines[i].line.words);
+            let mut merged = std::mem::take(&mut lines[j].line.words);
+            for word in words {
+                if !merged.iter().any(|kept| is_duplicate(kept, &word)) {
+                    merged.push(word);
+                }
+            }
+            merged.sort_by(|a, b| a.bbox.x.total_cmp(&b.bbox.x));
+            lines[j].line = Line::from_words(merged);
+        }
+    }
+
+    // Rebuild the hierarchy from what is left
+    let mut blocks: Vec<Block> = Vec::new();
+    let mut paragraph_lines: Vec<Line> = Vec::new();
+    let mut paragraphs: Vec<Paragraph> = Vec::new();
+    let mut current: Option<(usize, usize)> = None;
+    for tile_line in lines.into_iter().filter(|l| !l.line.words.is_empty()) {
+        let key = (tile_line.block, tile_line.paragraph);
+        if let Some((block, paragraph)) = current {
+            if key != (block, paragraph) && !paragraph_lines.is_empty() {
+                paragraphs.push(Paragraph::from_lines(std::mem::take(&mut paragraph_lines)));
+            }
+            if key.0 != block && !paragraphs.is_empty() {
+                blocks.push(Block::from_paragraphs(std::mem::take(&mut paragraphs)));
+            }
+        }
+        current = Some(key);
+        paragraph_lines.push(tile_line.line);
+    }
+    if !paragraph_lines.is_empty() {
+        paragraphs.push(Paragraph::from_lines(paragraph_lines));
+    }
+    if !paragraphs.is_empty() {
+        blocks.push(Block::from_paragraphs(paragraphs));
+    }
+
+    OcrResult {
+        confidence_reported,
+        provider,
+        ..OcrResult::new(vec![Page::new(width, height, blocks)])
+    }
+}
+
+/// Whether two lines share most of their height and nearly touch horizontally.
+fn same_row(a: &BoundingBox, b: &BoundingBox) -> bool {
+    let shared = a.bottom().min(b.bottom()) - a.y.max(b.y);
+    let gap = a.x.max(b.x) - a.right().min(b.right());
+    shared >= 0.5 * a.height.min(b.height) && gap <= 2.0 * a.height.max(b.height)
+}
+
+/// The same word recognized from two tiles: same text, mostly the same place.
+fn is_duplicate(a: &Word, b: &Word) -> bool {
+    if a.text != b.text {
+        return false;
+    }
+    let width = a.bbox.right().min(b.bbox.right()) - a.bbox.x.max(b.bbox.x);
+    let height = a.bbox.bottom().min(b.bbox.bottom()) - a.bbox.y.max(b.bbox.y);
+    if width <= 0.0 || height <= 0.0 {
+        return false;
+    }
+    let intersection = width * height;
+    let union = a.bbox.width * a.bbox.height + b.bbox.width * b.bbox.height - intersection;
+    intersection / union > 0.5
+}
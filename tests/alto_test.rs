@@ -0,0 +1,156 @@
+#[cfg(test)]
+mod tests {
+    use uni_ocr::{AltoOptions, BoundingBox, Line, MeasurementUnit, OcrResult, Word};
+
+    const ALTO_NS: &str = "http://www.loc.gov/standards/alto/ns-v4#";
+
+    fn parse(alto: &str) -> roxmltree::Document<'_> {
+        roxmltree::Document::parse(alto).expect("ALTO is not well-formed XML")
+    }
+
+    fn sample() -> OcrResult {
+        let mut result = OcrResult::from_lines(
+            600,
+            300,
+            vec![
+                Line::from_words(vec![
+                    Word::new("Vec<u8>", BoundingBox::new(30.0, 60.0, 120.0, 30.0), 0.96),
+                    Word::new("&", BoundingBox::new(165.0, 60.0, 15.0, 30.0), 0.9),
+                ]),
+                Line::from_words(vec![Word::new(
+                    "pool_size",
+                    BoundingBox::new(30.0, 120.0, 150.0, 30.0),
+                    0.5,
+                )]),
+            ],
+        );
+        result.provider = Some("tesseract".to_string());
+        result
+    }
+
+    /// Element children of `node` in the ALTO namespace, by local name.
+    fn children<'a>(node: roxmltree::Node<'a, 'a>) -> Vec<(String, roxmltree::Node<'a, 'a>)> {
+        node.children()
+            .filter(|n| n.is_element())
+            .map(|n| {
+                assert_eq!(n.tag_name().namespace(), Some(ALTO_NS));
+                (n.tag_name().name().to_string(), n)
+            })
+            .collect()
+    }
+
+    fn names(nodes: &[(String, roxmltree::Node)]) -> Vec<String> {
+        nodes.iter().map(|(name, _)| name.clone()).collect()
+    }
+
+    #[test]
+    fn test_alto_structure() {
+        let alto = sample().to_alto();
+        let document = parse(&alto);
+        let root = document.root_element();
+        assert_eq!(root.tag_name().name(), "alto");
+        assert_eq!(root.tag_name().namespace(), Some(ALTO_NS));
+
+        // Schema order: Description, then Layout
+        let top = children(root);
+        assert_eq!(names(&top), ["Description", "Layout"]);
+        let description = children(top[0].1);
+        assert_eq!(names(&description)[0], "MeasurementUnit");
+        assert_eq!(description[0].1.text(), Some("pixel"));
+
+        let pages = children(top[1].1);
+        assert_eq!(names(&pages), ["Page"]);
+        let page = pages[0].1;
+        assert_eq!(page.attribute("ID"), Some("page_1"));
+        assert_eq!(page.attribute("PHYSICAL_IMG_NR"), Some("1"));
+        assert_eq!(page.attribute("WIDTH"), Some("600"));
+        assert_eq!(page.attribute("HEIGHT"), Some("300"));
+
+        let print_space = children(page);
+        assert_eq!(names(&print_space), ["PrintSpace"]);
+        let blocks = children(print_space[0].1);
+        assert_eq!(names(&blocks), ["TextBlock"]);
+        let lines = children(blocks[0].1);
+        assert_eq!(names(&lines), ["TextLine", "TextLine"]);
+        assert_eq!(lines[0].1.attribute("HPOS"), Some("30"));
+        assert_eq!(lines[0].1.attribute("WIDTH"), Some("150"));
+
+        let strings = children(lines[0].1);
+        assert_eq!(names(&strings), ["String", "SP", "String"]);
+        let word = strings[0].1;
+        assert_eq!(word.attribute("CONTENT"), Some("Vec<u8>"));
+        assert_eq!(word.attribute("HPOS"), Some("30"));
+        assert_eq!(word.attribute("VPOS"), Some("60"));
+        assert_eq!(word.attribute("WIDTH"), Some("120"));
+        assert_eq!(word.attribute("HEIGHT"), Some("30"));
+        assert_eq!(word.attribute("WC"), Some("0.96"));
+        assert_eq!(strings[2].1.attribute("CONTENT"), Some("&"));
+
+        // IDs are unique across the document
+        let ids: Vec<_> = document
+            .descendants()
+            .filter_map(|n| n.attribute("ID"))
+            .collect();
+        let mut unique = ids.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(ids.len(), unique.len());
+    }
+
+    #[test]
+    fn test_alto_measurement_units() {
+        let result = sample();
+
+        let mm10 = result.to_alto_with(
+            &AltoOptions::default()
+                .measurement_unit(MeasurementUnit::Mm10)
+                .dpi(300.0),
+        );
+        let document = parse(&mm10);
+        let unit = document
+            .descendants()
+            .find(|n| n.has_tag_name((ALTO_NS, "MeasurementUnit")))
+            .unwrap();
+        assert_eq!(unit.text(), Some("mm10"));
+        let word = document
+            .descendants()
+            .find(|n| n.has_tag_name((ALTO_NS, "String")))
+            .unwrap();
+        // 30px at 300 dpi is 1/10 inch, 2.54mm
+        assert_eq!(word.attribute("HPOS"), Some("25"));
+        assert_eq!(word.attribute("WIDTH"), Some("102"));
+
+        let inch1200 = result.to_alto_with(
+            &AltoOptions::default()
+                .measurement_unit(MeasurementUnit::Inch1200)
+                .dpi(300.0),
+        );
+        let document = parse(&inch1200);
+        let page = document
+            .descendants()
+            .find(|n| n.has_tag_name((ALTO_NS, "Page")))
+            .unwrap();
+        assert_eq!(page.attribute("WIDTH"), Some("2400"));
+        assert_eq!(page.attribute("HEIGHT"), Some("1200"));
+    }
+
+    #[test]
+    fn test_alto_without_boxes_or_confidence() {
+        let mut result = OcrResult::from_lines(
+            0,
+            0,
+            vec![Line::from_words(vec![Word::new(
+                "hello",
+                BoundingBox::default(),
+                1.0,
+            )])],
+        );
+        result.confidence_reported = false;
+
+        let alto = result.to_alto();
+        parse(&alto);
+        assert!(alto.contains(r#"<String ID="string_1_1" CONTENT="hello"/>"#));
+        assert!(!alto.contains("WC="));
+        assert!(!alto.contains("HPOS"));
+    }
+}
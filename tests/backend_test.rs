@@ -0,0 +1,343 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{one_line, FnBackend};
+    use async_trait::async_trait;
+    use futures_util::{stream, StreamExt};
+    use image::DynamicImage;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+    use uni_ocr::{
+        cancelled, run_blocking, BoundingBox, Capabilities, Credentials, Language, OcrBackend,
+        OcrEngine, OcrError, OcrOptions, OcrProvider, OcrResult, Result, Word,
+    };
+
+    /// In-house engine stand-in that always recognizes the same two words.
+    fn fixed(image: &DynamicImage) -> Result<OcrResult> {
+        let words = vec![
+            Word::new("sure", BoundingBox::new(0.0, 0.0, 10.0, 10.0), 0.95),
+            Word::new("maybe", BoundingBox::new(20.0, 0.0, 10.0, 10.0), 0.4),
+        ];
+        one_line(image, words)
+    }
+
+    #[tokio::test]
+    async fn test_custom_backend() {
+        let provider = FnBackend::new("fixed", fixed).provider();
+        assert!(format!("{:?}", provider).contains("fixed"));
+        assert_eq!(provider.backend().unwrap().name(), "fixed");
+
+        let engine = OcrEngine::new(provider)
+            .unwrap()
+            .with_options(OcrOptions::default().confidence_threshold(0.5));
+
+        let result = engine
+            .recognize_image(&DynamicImage::new_rgb8(64, 32))
+            .await
+            .unwrap();
+        assert_eq!(result.text(), "sure");
+        assert_eq!((result.pages[0].width, result.pages[0].height), (64, 32));
+    }
+
+    #[tokio::test]
+    async fn test_custom_backend_digits_only() {
+        let engine = OcrEngine::new(FnBackend::new("fixed", fixed).provider())
+            .unwrap()
+            .with_options(OcrOptions::default().digits_only());
+
+        let result = engine
+            .recognize_image(&DynamicImage::new_rgb8(64, 32))
+            .await
+            .unwrap();
+        // "sure" and "maybe" keep only their digit look-alikes
+        assert_eq!(result.text(), "5 6");
+    }
+
+    #[test]
+    fn test_builtin_backends() {
+        let tesseract = OcrProvider::Tesseract.backend().unwrap();
+        assert_eq!(tesseract.name(), "tesseract");
+        assert!(tesseract.capabilities().blocking);
+
+        #[cfg(not(target_os = "macos"))]
+        assert!(OcrProvider::MacOS.backend().is_err());
+    }
+
+    /// Backend that is never able to run.
+    fn broken(_image: &DynamicImage) -> Result<OcrResult> {
+        Err(OcrError::ProviderUnavailable("broken".to_string()))
+    }
+
+    /// Backend recognizing one word with a fixed, low confidence.
+    fn doubtful(image: &DynamicImage) -> Result<OcrResult> {
+        let word = Word::new("rnaybe", BoundingBox::new(0.0, 0.0, 10.0, 10.0), 0.2);
+        one_line(image, vec![word])
+    }
+
+    #[tokio::test]
+    async fn test_fallback_on_error() {
+        let engine = OcrEngine::new(OcrProvider::Auto)
+            .unwrap()
+            .with_fallback_chain(vec![
+                FnBackend::new("broken", broken).provider(),
+                FnBackend::new("fixed", fixed).provider(),
+            ]);
+
+        let result = engine
+            .recognize_image(&DynamicImage::new_rgb8(8, 8))
+            .await
+            .unwrap();
+        assert_eq!(result.provider.as_deref(), Some("fixed"));
+    }
+
+    #[tokio::test]
+    async fn test_fallback_on_low_confidence() {
+        let chain = vec![
+            FnBackend::new("doubtful", doubtful).provider(),
+            FnBackend::new("fixed", fixed).provider(),
+        ];
+        let engine = OcrEngine::new(OcrProvider::Auto)
+            .unwrap()
+            .with_fallback_chain(chain.clone())
+            .with_fallback_confidence(0.5);
+        let result = engine
+            .recognize_image(&DynamicImage::new_rgb8(8, 8))
+            .await
+            .unwrap();
+        assert_eq!(result.provider.as_deref(), Some("fixed"));
+
+        // Without a floor the first answer wins
+        let engine = OcrEngine::new(OcrProvider::Auto)
+            .unwrap()
+            .with_fallback_chain(chain);
+        let result = engine
+            .recognize_image(&DynamicImage::new_rgb8(8, 8))
+            .await
+            .unwrap();
+        assert_eq!(result.provider.as_deref(), Some("doubtful"));
+    }
+
+    #[tokio::test]
+    async fn test_fallback_returns_last_error() {
+        let engine = OcrEngine::new(OcrProvider::Auto)
+            .unwrap()
+            .with_fallback_chain(vec![FnBackend::new("broken", broken).provider()]);
+        let err = engine
+            .recognize_image(&DynamicImage::new_rgb8(8, 8))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, OcrError::ProviderUnavailable(_)));
+    }
+
+    #[tokio::test]
+    async fn test_available_providers_includes_custom() {
+        let engine = OcrEngine::new(FnBackend::new("fixed", fixed).provider()).unwrap();
+        let available = engine.available_providers().await;
+
+        assert!(available.iter().all(|status| status.available));
+        let fixed = available
+            .iter()
+            .find(|status| status.name == "fixed")
+            .unwrap();
+        assert_eq!(fixed.languages, vec![Language::English]);
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[tokio::test]
+    async fn test_probe_unavailable_platform_provider() {
+        let status = OcrProvider::Windows.probe().await;
+        assert!(!status.available);
+        assert!(status.detail.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_auto_probe_follows_the_fallback_chain() {
+        let backend = FnBackend::new("fixed", fixed);
+        let unreachable = OcrProvider::Http {
+            credentials: Credentials {
+                api_url: "http://127.0.0.1:9/ocr".to_string(),
+                timeout_ms: 500,
+                ..Credentials::default()
+            },
+        };
+        let engine = OcrEngine::new(OcrProvider::Auto)
+            .unwrap()
+            .with_fallback_chain(vec![unreachable.clone(), backend.provider()]);
+        let status = engine.probe().await;
+        assert!(status.available);
+        assert_eq!(status.name, "fixed");
+
+        let engine = OcrEngine::new(OcrProvider::Auto)
+            .unwrap()
+            .with_fallback_chain(vec![unreachable]);
+        let status = engine.probe().await;
+        assert!(!status.available);
+        assert!(status.detail.unwrap().contains("unreachable"));
+    }
+
+    /// Blocking backend that works 300ms in each of its `sections`, tracking how many run at
+    /// once.
+    #[derive(Default)]
+    struct SlowBackend {
+        sections: usize,
+        started: Arc<AtomicUsize>,
+        running: Arc<AtomicUsize>,
+        most_running: Arc<AtomicUsize>,
+    }
+
+    impl SlowBackend {
+        fn new(sections: usize) -> Arc<Self> {
+            Arc::new(Self {
+                sections,
+                ..Self::default()
+            })
+        }
+
+        async fn section(&self, width: u32, height: u32) -> Result<OcrResult> {
+            let started = self.started.clone();
+            let running = self.running.clone();
+            let most_running = self.most_running.clone();
+            run_blocking(move || {
+                started.fetch_add(1, Ordering::SeqCst);
+                let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                most_running.fetch_max(now, Ordering::SeqCst);
+                std::thread::sleep(Duration::from_millis(300));
+                running.fetch_sub(1, Ordering::SeqCst);
+                Ok(OcrResult::from_lines(width, height, Vec::new()))
+            })
+            .await
+        }
+    }
+
+    #[async_trait]
+    impl OcrBackend for SlowBackend {
+        fn name(&self) -> &str {
+            "slow"
+        }
+
+        fn supported_languages(&self) -> Vec<Language> {
+            vec![Language::English]
+        }
+
+        fn capabilities(&self) -> Capabilities {
+            Capabilities {
+                word_boxes: true,
+                confidence: true,
+                blocking: true,
+            }
+        }
+
+        async fn recognize(
+            &self,
+            image: &DynamicImage,
+            _options: &OcrOptions,
+        ) -> Result<OcrResult> {
+            let (width, height) = (image.width(), image.height());
+            for _ in 1..self.sections {
+                self.section(width, height).await?;
+            }
+            self.section(width, height).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_timed_out_blocking_work_keeps_its_permit() {
+        let backend = SlowBackend::new(1);
+        let engine = OcrEngine::new(OcrProvider::Custom(backend.clone()))
+            .unwrap()
+            .with_max_concurrency(1)
+            .with_options(OcrOptions::default().timeout(Duration::from_millis(200)));
+        let image = DynamicImage::new_rgb8(8, 8);
+
+        // The first call gives up while its work is still running
+        let err = engine.recognize_image(&image).await.unwrap_err();
+        assert!(matches!(err, OcrError::Timeout(_)));
+        assert_eq!(backend.running.load(Ordering::SeqCst), 1);
+
+        // The second one only starts once the first has returned
+        let _ = engine.recognize_image(&image).await;
+        tokio::time::sleep(Duration::from_millis(400)).await;
+        assert_eq!(backend.started.load(Ordering::SeqCst), 2);
+        assert_eq!(backend.most_running.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_blocking_sections_share_one_permit() {
+        let backend = SlowBackend::new(2);
+        let engine = OcrEngine::new(OcrProvider::Custom(backend.clone()))
+            .unwrap()
+            .with_max_concurrency(1);
+        let image = DynamicImage::new_rgb8(8, 8);
+
+        // The second section of one call still holds the permit the other call waits for
+        let (first, second) = tokio::join!(
+            engine.recognize_image(&image),
+            engine.recognize_image(&image)
+        );
+        first.unwrap();
+        second.unwrap();
+        assert_eq!(backend.started.load(Ordering::SeqCst), 4);
+        assert_eq!(backend.most_running.load(Ordering::SeqCst), 1);
+    }
+
+    /// Blocking backend that works until its caller stops waiting, counting cancellations.
+    #[derive(Default)]
+    struct EndlessBackend {
+        cancellations: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl OcrBackend for EndlessBackend {
+        fn name(&self) -> &str {
+            "endless"
+        }
+
+        fn supported_languages(&self) -> Vec<Language> {
+            vec![Language::English]
+        }
+
+        fn capabilities(&self) -> Capabilities {
+            Capabilities {
+                word_boxes: true,
+                confidence: true,
+                blocking: true,
+            }
+        }
+
+        async fn recognize(
+            &self,
+            _image: &DynamicImage,
+            _options: &OcrOptions,
+        ) -> Result<OcrResult> {
+            let cancellations = self.cancellations.clone();
+            run_blocking(move || {
+                while !cancelled() {
+                    std::thread::sleep(Duration::from_millis(5));
+                }
+                cancellations.fetch_add(1, Ordering::SeqCst);
+                Err(OcrError::Backend("cancelled".to_string()))
+            })
+            .await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dropping_a_stream_cancels_running_work() {
+        let backend = Arc::new(EndlessBackend::default());
+        let engine = OcrEngine::new(OcrProvider::Custom(backend.clone()))
+            .unwrap()
+            .with_max_concurrency(2);
+        let images = (0..4).map(|_| DynamicImage::new_rgb8(8, 8));
+
+        let mut results = Box::pin(engine.recognize_stream(stream::iter(images)));
+        let next = tokio::time::timeout(Duration::from_millis(100), results.next()).await;
+        assert!(next.is_err());
+        drop(results);
+
+        // Both running items notice, the two others never start
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(backend.cancellations.load(Ordering::SeqCst), 2);
+    }
+}
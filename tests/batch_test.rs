@@ -0,0 +1,116 @@
+#[cfg(test)]
+mod tests {
+    use futures_util::{stream, StreamExt};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    use uni_ocr::{Credentials, ImageSource, OcrEngine, OcrError, OcrProvider};
+
+    /// Minimal OCR server answering every request with the same recognized text.
+    async fn serve_text(text: &'static str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                tokio::spawn(async move {
+                    let mut request = Vec::new();
+                    let mut buf = vec![0u8; 64 * 1024];
+                    // Read headers and the announced body before answering
+                    loop {
+                        let n = socket.read(&mut buf).await.unwrap();
+                        if n == 0 {
+                            return;
+                        }
+                        request.extend_from_slice(&buf[..n]);
+                        let head = String::from_utf8_lossy(&request);
+                        if let Some(end) = head.find("\r\n\r\n") {
+                            let length = head[..end]
+                                .lines()
+                                .find_map(|l| {
+                                    l.to_ascii_lowercase()
+                                        .strip_prefix("content-length:")
+                                        .map(|v| v.trim().parse::<usize>().unwrap())
+                                })
+                                .unwrap_or(0);
+                            if request.len() >= end + 4 + length {
+                                break;
+                            }
+                        }
+                    }
+                    let body = format!(r#"{{"text":"{}","confidence":0.9}}"#, text);
+                    let response = format!(
+                        "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                        body.len(),
+                        body
+                    );
+                    socket.write_all(response.as_bytes()).await.unwrap();
+                    socket.shutdown().await.ok();
+                });
+            }
+        });
+        format!("http://{}/ocr", addr)
+    }
+
+    #[tokio::test]
+    async fn test_batch_keeps_order_and_isolates_failures() {
+        let credentials = Credentials {
+            api_url: serve_text("hello batch").await,
+            ..Credentials::default()
+        };
+        let engine = OcrEngine::new(OcrProvider::Http { credentials })
+            .unwrap()
+            .with_max_concurrency(2);
+
+        let sources = vec![
+            ImageSource::from(image::DynamicImage::new_rgb8(16, 16)),
+            ImageSource::from("does/not/exist.png"),
+            ImageSource::from(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/testing_OCR.png"
+            )),
+        ];
+        let results = engine.recognize_batch(sources).await;
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].as_ref().unwrap().text(), "hello batch");
+        assert!(
+            matches!(results[1], Err(OcrError::InvalidImage(_))),
+            "unexpected result: {:?}",
+            results[1]
+        );
+        assert_eq!(results[2].as_ref().unwrap().text(), "hello batch");
+    }
+
+    #[tokio::test]
+    async fn test_stream_yields_every_index() {
+        let credentials = Credentials {
+            api_url: serve_text("hello stream").await,
+            ..Credentials::default()
+        };
+        let engine = OcrEngine::new(OcrProvider::Http { credentials })
+            .unwrap()
+            .with_max_concurrency(3);
+
+        let sources = stream::iter((0..10).map(|i| {
+            if i == 4 {
+                ImageSource::from("does/not/exist.png")
+            } else {
+                ImageSource::from(image::DynamicImage::new_rgb8(16, 16))
+            }
+        }));
+        let mut results: Vec<_> = engine.recognize_stream(sources).collect().await;
+        results.sort_by_key(|(index, _)| *index);
+
+        assert_eq!(
+            results.iter().map(|(index, _)| *index).collect::<Vec<_>>(),
+            (0..10).collect::<Vec<_>>()
+        );
+        for (index, result) in results {
+            if index == 4 {
+                assert!(result.is_err());
+            } else {
+                assert_eq!(result.unwrap().text(), "hello stream");
+            }
+        }
+    }
+}
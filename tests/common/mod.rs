@@ -0,0 +1,73 @@
+// Each test binary uses only part of this module
+#![allow(dead_code)]
+
+use async_trait::async_trait;
+use image::DynamicImage;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use uni_ocr::{
+    Capabilities, Language, Line, OcrBackend, OcrEngine, OcrOptions, OcrProvider, OcrResult,
+    Result, Word,
+};
+
+/// Custom backend that reads images with a plain function, counting its calls.
+pub struct FnBackend {
+    name: &'static str,
+    read: fn(&DynamicImage) -> Result<OcrResult>,
+    calls: AtomicUsize,
+}
+
+impl FnBackend {
+    pub fn new(name: &'static str, read: fn(&DynamicImage) -> Result<OcrResult>) -> Arc<Self> {
+        Arc::new(Self {
+            name,
+            read,
+            calls: AtomicUsize::new(0),
+        })
+    }
+
+    pub fn calls(&self) -> usize {
+        self.calls.load(Ordering::Relaxed)
+    }
+
+    pub fn provider(self: &Arc<Self>) -> OcrProvider {
+        OcrProvider::Custom(self.clone())
+    }
+
+    pub fn engine(self: &Arc<Self>) -> OcrEngine {
+        OcrEngine::new(self.provider()).unwrap()
+    }
+}
+
+#[async_trait]
+impl OcrBackend for FnBackend {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn supported_languages(&self) -> Vec<Language> {
+        vec![Language::English]
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            word_boxes: true,
+            confidence: true,
+            blocking: false,
+        }
+    }
+
+    async fn recognize(&self, image: &DynamicImage, _options: &OcrOptions) -> Result<OcrResult> {
+        self.calls.fetch_add(1, Ordering::Relaxed);
+        (self.read)(image)
+    }
+}
+
+/// A result with one line of `words` on a page the size of `image`.
+pub fn one_line(image: &DynamicImage, words: Vec<Word>) -> Result<OcrResult> {
+    Ok(OcrResult::from_lines(
+        image.width(),
+        image.height(),
+        vec![Line::from_words(words)],
+    ))
+}
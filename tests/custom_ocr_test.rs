@@ -64,7 +64,24 @@ rm -rf venv app.py
 mod tests {
     use image::GenericImageView;
     use std::path::PathBuf;
-    use uni_ocr::{Credentials, Language, OcrEngine, OcrOptions, OcrProvider};
+    use std::time::Duration;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    use uni_ocr::{Credentials, Language, OcrEngine, OcrError, OcrOptions, OcrProvider};
+
+    /// Serves a single canned HTTP response on a local port and returns its URL.
+    async fn serve_once(response: &'static str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = vec![0u8; 1 << 20];
+            let _ = socket.read(&mut buf).await;
+            socket.write_all(response.as_bytes()).await.unwrap();
+            socket.shutdown().await.ok();
+        });
+        format!("http://{}/ocr", addr)
+    }
 
     #[tokio::test]
     #[ignore]
@@ -93,16 +110,17 @@ mod tests {
             timeout_ms: 5000,
         };
 
-        let engine = OcrEngine::new(OcrProvider::Custom { credentials })
+        let engine = OcrEngine::new(OcrProvider::Http { credentials })
             .unwrap()
             .with_options(OcrOptions::default().languages(vec![Language::English]));
 
         // Perform the custom OCR.
-        let (ocr_text, structured_data, confidence) = engine.recognize_image(&image).await.unwrap();
+        let result = engine.recognize_image(&image).await.unwrap();
+        let ocr_text = result.text();
 
         println!("OCR text: {:?}", ocr_text);
-        println!("Structured data: {:?}", structured_data);
-        println!("Confidence: {:?}", confidence);
+        println!("Structured data: {:?}", result);
+        println!("Confidence: {:?}", result.confidence());
 
         // Check some basic assumption about the output
         assert!(
@@ -135,11 +153,11 @@ mod tests {
             timeout_ms: 30000000,
         };
 
-        let engine = OcrEngine::new(OcrProvider::Custom { credentials })
+        let engine = OcrEngine::new(OcrProvider::Http { credentials })
             .unwrap()
             .with_options(OcrOptions::default().languages(vec![Language::Chinese]));
 
-        let (ocr_text, _, _) = engine.recognize_image(&image).await.unwrap();
+        let ocr_text = engine.recognize_image(&image).await.unwrap().text();
 
         println!("OCR text: {:?}", ocr_text);
         assert!(
@@ -148,4 +166,130 @@ mod tests {
             ocr_text
         );
     }
+
+    #[tokio::test]
+    async fn test_custom_ocr_http_error() {
+        let api_url = serve_once(
+            "HTTP/1.1 503 Service Unavailable\r\ncontent-length: 0\r\nconnection: close\r\n\r\n",
+        )
+        .await;
+        let credentials = Credentials {
+            api_url,
+            ..Credentials::default()
+        };
+        let engine = OcrEngine::new(OcrProvider::Http { credentials }).unwrap();
+
+        let image = image::DynamicImage::new_rgb8(32, 32);
+        let err = engine.recognize_image(&image).await.unwrap_err();
+        assert!(
+            matches!(err, OcrError::Http { status: 503 }),
+            "unexpected error: {:?}",
+            err
+        );
+    }
+
+    #[tokio::test]
+    async fn test_custom_ocr_decode_error() {
+        let api_url = serve_once(
+            "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: 9\r\nconnection: close\r\n\r\nnot json!",
+        )
+        .await;
+        let credentials = Credentials {
+            api_url,
+            ..Credentials::default()
+        };
+        let engine = OcrEngine::new(OcrProvider::Http { credentials }).unwrap();
+
+        let image = image::DynamicImage::new_rgb8(32, 32);
+        let err = engine.recognize_image(&image).await.unwrap_err();
+        assert!(
+            matches!(err, OcrError::Decode(_)),
+            "unexpected error: {:?}",
+            err
+        );
+    }
+
+    #[tokio::test]
+    async fn test_custom_ocr_engine_timeout() {
+        // Accept the connection but never answer
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (_socket, _) = listener.accept().await.unwrap();
+            tokio::time::sleep(Duration::from_secs(60)).await;
+        });
+
+        let credentials = Credentials {
+            api_url: format!("http://{}/ocr", addr),
+            api_key: "".to_string(),
+            timeout_ms: 60_000,
+        };
+        let engine = OcrEngine::new(OcrProvider::Http { credentials })
+            .unwrap()
+            .with_options(OcrOptions::default().timeout(Duration::from_millis(200)));
+
+        let image = image::DynamicImage::new_rgb8(32, 32);
+        let err = engine.recognize_image(&image).await.unwrap_err();
+        assert!(
+            matches!(err, OcrError::Timeout(t) if t == Duration::from_millis(200)),
+            "unexpected error: {:?}",
+            err
+        );
+    }
+
+    #[tokio::test]
+    async fn test_custom_ocr_engine_timeout_overrides_credentials() {
+        // Answer after longer than the credentials allow, but within the engine timeout
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = vec![0u8; 1 << 20];
+            let _ = socket.read(&mut buf).await;
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            let body = r#"{"text":"late","confidence":0.9}"#;
+            let response = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+            socket.shutdown().await.ok();
+        });
+
+        let credentials = Credentials {
+            api_url: format!("http://{}/ocr", addr),
+            api_key: "".to_string(),
+            timeout_ms: 50,
+        };
+        let engine = OcrEngine::new(OcrProvider::Http { credentials })
+            .unwrap()
+            .with_options(OcrOptions::default().timeout(Duration::from_secs(5)));
+
+        let image = image::DynamicImage::new_rgb8(32, 32);
+        let result = engine.recognize_image(&image).await.unwrap();
+        assert_eq!(result.text(), "late");
+    }
+
+    #[tokio::test]
+    async fn test_http_probe() {
+        let credentials = Credentials {
+            api_url: serve_once("HTTP/1.1 405 Method Not Allowed\r\ncontent-length: 0\r\n\r\n")
+                .await,
+            ..Credentials::default()
+        };
+        let status = OcrProvider::Http { credentials }.probe().await;
+        assert!(status.available, "{:?}", status.detail);
+
+        // Nothing listens on a freshly released port
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+        let credentials = Credentials {
+            api_url: format!("http://{}/ocr", addr),
+            ..Credentials::default()
+        };
+        let status = OcrProvider::Http { credentials }.probe().await;
+        assert!(!status.available);
+    }
 }
@@ -0,0 +1,144 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{one_line, FnBackend};
+    use fax::encoder::Encoder;
+    use fax::{Color, VecWriter};
+    use image::codecs::gif::GifEncoder;
+    use image::{DynamicImage, Frame, GrayImage, Luma, Rgba, RgbaImage};
+    use std::sync::Arc;
+    use tiff::encoder::{colortype, TiffEncoder};
+    use uni_ocr::{BoundingBox, ImageSource, OcrResult, Result, Word};
+
+    /// Reads the gray level of the top-left pixel of each image it is given.
+    fn gray_level(image: &DynamicImage) -> Result<OcrResult> {
+        let level = image.to_luma8().get_pixel(0, 0)[0];
+        let word = Word::new(
+            format!("level{}", level),
+            BoundingBox::new(0.0, 0.0, image.width() as f64, image.height() as f64),
+            0.9,
+        );
+        one_line(image, vec![word])
+    }
+
+    fn gray_level_backend() -> Arc<FnBackend> {
+        FnBackend::new("gray-level", gray_level)
+    }
+
+    #[tokio::test]
+    async fn test_multi_page_tiff() {
+        let file = tempfile::Builder::new().suffix(".tiff").tempfile().unwrap();
+        let mut tiff = TiffEncoder::new(file.reopen().unwrap()).unwrap();
+        for (level, width) in [(10u8, 40u32), (20, 50), (30, 60)] {
+            let page = GrayImage::from_pixel(width, 30, Luma([level]));
+            tiff.write_image::<colortype::Gray8>(width, 30, page.as_raw())
+                .unwrap();
+        }
+        drop(tiff);
+
+        let backend = gray_level_backend();
+        let result = backend
+            .engine()
+            .with_max_concurrency(1)
+            .recognize_file(file.path().to_str().unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(backend.calls(), 3);
+        assert_eq!(result.text(), "level10\nlevel20\nlevel30");
+        let pages: Vec<_> = result
+            .pages
+            .iter()
+            .map(|p| (p.number, p.width, p.height))
+            .collect();
+        assert_eq!(pages, [(1, 40, 30), (2, 50, 30), (3, 60, 30)]);
+        assert_eq!(result.provider.as_deref(), Some("gray-level"));
+    }
+
+    #[tokio::test]
+    async fn test_group_4_fax_tiff() {
+        // A bilevel page whose rows don't end on a byte, black in its top-left corner only
+        let (width, height) = (50u16, 20u16);
+        let mut encoder = Encoder::new(VecWriter::new());
+        for y in 0..height {
+            let row = (0..width).map(|x| {
+                if x < 10 && y < 5 {
+                    Color::Black
+                } else {
+                    Color::White
+                }
+            });
+            encoder.encode_line(row, width).unwrap();
+        }
+        let data = encoder.finish().unwrap().finish();
+        let file = tempfile::Builder::new().suffix(".tiff").tempfile().unwrap();
+        std::fs::write(
+            file.path(),
+            fax::tiff::wrap(&data, width as u32, height as u32),
+        )
+        .unwrap();
+
+        let backend = gray_level_backend();
+        let result = backend
+            .engine()
+            .recognize_file(file.path().to_str().unwrap())
+            .await
+            .unwrap();
+        assert_eq!(result.text(), "level0");
+        assert_eq!((result.pages[0].width, result.pages[0].height), (50, 20));
+
+        let frames = ImageSource::Path(file.path().into()).load_frames().unwrap();
+        let expected =
+            GrayImage::from_fn(50, 20, |x, y| Luma([if x < 10 && y < 5 { 0 } else { 255 }]));
+        assert_eq!(frames, [DynamicImage::ImageLuma8(expected)]);
+    }
+
+    #[tokio::test]
+    async fn test_animated_gif() {
+        let file = tempfile::Builder::new().suffix(".gif").tempfile().unwrap();
+        let mut gif = GifEncoder::new(file.reopen().unwrap());
+        let frames = [40u8, 80, 120, 160].map(|level| {
+            Frame::new(RgbaImage::from_pixel(
+                32,
+                16,
+                Rgba([level, level, level, 255]),
+            ))
+        });
+        gif.encode_frames(frames).unwrap();
+        drop(gif);
+
+        // Frames are recognized in parallel but kept in order
+        let backend = gray_level_backend();
+        let result = backend
+            .engine()
+            .with_max_concurrency(4)
+            .recognize_file(file.path().to_str().unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(backend.calls(), 4);
+        assert_eq!(result.pages.len(), 4);
+        assert_eq!(result.text(), "level40\nlevel80\nlevel120\nlevel160");
+        assert_eq!(result.pages[3].number, 4);
+    }
+
+    #[tokio::test]
+    async fn test_single_frame_files_are_unchanged() {
+        let file = tempfile::Builder::new().suffix(".png").tempfile().unwrap();
+        GrayImage::from_pixel(20, 10, Luma([7]))
+            .save_with_format(file.path(), image::ImageFormat::Png)
+            .unwrap();
+
+        let backend = gray_level_backend();
+        let result = backend
+            .engine()
+            .recognize_file(file.path().to_str().unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(backend.calls(), 1);
+        assert_eq!(result.pages.len(), 1);
+        assert_eq!(result.text(), "level7");
+    }
+}
@@ -0,0 +1,97 @@
+#[cfg(test)]
+mod tests {
+    use uni_ocr::{BoundingBox, Line, OcrResult, Word};
+
+    fn parse(hocr: &str) -> roxmltree::Document<'_> {
+        let options = roxmltree::ParsingOptions {
+            allow_dtd: true,
+            ..Default::default()
+        };
+        roxmltree::Document::parse_with_options(hocr, options).expect("hOCR is not well-formed XML")
+    }
+
+    fn sample() -> OcrResult {
+        let mut result = OcrResult::from_lines(
+            400,
+            100,
+            vec![
+                Line::from_words(vec![
+                    Word::new("Vec<u8>", BoundingBox::new(10.0, 10.0, 80.0, 20.0), 0.965),
+                    Word::new("&", BoundingBox::new(100.0, 10.0, 10.0, 20.0), 0.9),
+                ]),
+                Line::from_words(vec![Word::new(
+                    "pool_size",
+                    BoundingBox::new(10.0, 40.0, 80.4, 20.0),
+                    0.5,
+                )]),
+            ],
+        );
+        result.provider = Some("tesseract".to_string());
+        result
+    }
+
+    #[test]
+    fn test_hocr_structure() {
+        let hocr = sample().to_hocr();
+        let document = parse(&hocr);
+
+        let class_count = |class: &str| {
+            document
+                .descendants()
+                .filter(|n| n.attribute("class") == Some(class))
+                .count()
+        };
+        assert_eq!(class_count("ocr_page"), 1);
+        assert_eq!(class_count("ocr_carea"), 1);
+        assert_eq!(class_count("ocr_par"), 1);
+        assert_eq!(class_count("ocr_line"), 2);
+        assert_eq!(class_count("ocrx_word"), 3);
+
+        let page = document
+            .descendants()
+            .find(|n| n.attribute("class") == Some("ocr_page"))
+            .unwrap();
+        assert_eq!(page.attribute("title"), Some("bbox 0 0 400 100; ppageno 0"));
+
+        let words: Vec<_> = document
+            .descendants()
+            .filter(|n| n.attribute("class") == Some("ocrx_word"))
+            .collect();
+        assert_eq!(words[0].text(), Some("Vec<u8>"));
+        assert_eq!(
+            words[0].attribute("title"),
+            Some("bbox 10 10 90 30; x_wconf 97")
+        );
+        assert_eq!(words[1].text(), Some("&"));
+        assert_eq!(
+            words[2].attribute("title"),
+            Some("bbox 10 40 90 60; x_wconf 50")
+        );
+
+        let line = words[0].parent().unwrap();
+        assert_eq!(line.attribute("class"), Some("ocr_line"));
+        assert_eq!(line.attribute("title"), Some("bbox 10 10 110 30"));
+
+        assert!(hocr.contains(r#"<meta name="ocr-system" content="uni-ocr "#));
+        assert!(hocr.contains("(tesseract)"));
+    }
+
+    #[test]
+    fn test_hocr_without_boxes_or_confidence() {
+        let mut result = OcrResult::from_lines(
+            0,
+            0,
+            vec![Line::from_words(vec![Word::new(
+                "hello",
+                BoundingBox::default(),
+                1.0,
+            )])],
+        );
+        result.confidence_reported = false;
+
+        let hocr = result.to_hocr();
+        parse(&hocr);
+        assert!(hocr.contains(r#"<span class="ocrx_word" id="word_1_1">hello</span>"#));
+        assert!(!hocr.contains("x_wconf"));
+    }
+}
@@ -0,0 +1,400 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{one_line, FnBackend};
+    use fax::encoder::Encoder;
+    use fax::{Color, VecWriter};
+    use image::{DynamicImage, GrayImage, Luma, Rgb, RgbImage};
+    use lopdf::{dictionary, Document, Object, Stream};
+    use uni_ocr::{
+        BoundingBox, Line, OcrError, OcrOptions, OcrResult, Page, PdfWriter, Result, Word,
+    };
+
+    fn scan() -> (DynamicImage, OcrResult) {
+        let image = DynamicImage::ImageLuma8(GrayImage::from_pixel(600, 300, Luma([255])));
+        let result = OcrResult::from_lines(
+            600,
+            300,
+            vec![
+                Line::from_words(vec![
+                    Word::new("Hello", BoundingBox::new(30.0, 60.0, 150.0, 30.0), 0.9),
+                    Word::new("wörld", BoundingBox::new(200.0, 60.0, 150.0, 30.0), 0.9),
+                ]),
+                Line::from_words(vec![
+                    Word::new("日本語", BoundingBox::new(30.0, 120.0, 90.0, 30.0), 0.9),
+                    Word::new("unplaced", BoundingBox::default(), 0.9),
+                ]),
+            ],
+        );
+        (image, result)
+    }
+
+    #[test]
+    fn test_searchable_pdf() {
+        let (image, result) = scan();
+        let document = Document::load_mem(&result.to_pdf([&image]).unwrap()).unwrap();
+
+        let pages = document.get_pages();
+        assert_eq!(pages.len(), 1);
+        let page = document.get_dictionary(pages[&1]).unwrap();
+        // 600x300 pixels at 300 dpi
+        let media_box: Vec<f32> = page
+            .get(b"MediaBox")
+            .unwrap()
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|n| n.as_float().unwrap())
+            .collect();
+        assert_eq!(media_box, [0.0, 0.0, 144.0, 72.0]);
+
+        let images = document.get_page_images(pages[&1]).unwrap();
+        assert_eq!((images[0].width, images[0].height), (600, 300));
+        assert_eq!(images[0].color_space.as_deref(), Some("DeviceGray"));
+
+        // The text is invisible but extractable, one line per text object
+        let content = String::from_utf8(document.get_page_content(pages[&1]).unwrap()).unwrap();
+        assert!(content.contains("3 Tr"));
+        assert!(content.contains("1 0 0 1 7.20 50.40 Tm"));
+        let text = document.extract_text(&[1]).unwrap();
+        assert_eq!(text, "Hello wörld\n日本語\n");
+    }
+
+    #[test]
+    fn test_multi_page_pdf() {
+        let (gray, gray_result) = scan();
+        let color = DynamicImage::ImageRgb8(RgbImage::from_pixel(200, 100, Rgb([200, 220, 255])));
+        let color_result = OcrResult::from_lines(
+            200,
+            100,
+            vec![Line::from_words(vec![Word::new(
+                "second",
+                BoundingBox::new(10.0, 10.0, 100.0, 20.0),
+                0.9,
+            )])],
+        );
+
+        let mut pdf = PdfWriter::new().dpi(72.0);
+        pdf.add_page(&gray, &gray_result.pages[0])
+            .unwrap()
+            .add_page(&color, &color_result.pages[0])
+            .unwrap();
+        assert_eq!(pdf.page_count(), 2);
+        let document = Document::load_mem(&pdf.to_bytes().unwrap()).unwrap();
+
+        let pages = document.get_pages();
+        assert_eq!(pages.len(), 2);
+        let images = document.get_page_images(pages[&2]).unwrap();
+        assert_eq!(images[0].color_space.as_deref(), Some("DeviceRGB"));
+        assert_eq!(document.extract_text(&[2]).unwrap(), "second\n");
+    }
+
+    #[test]
+    fn test_multi_page_result_pdf() {
+        let (first, first_result) = scan();
+        let second = DynamicImage::ImageLuma8(GrayImage::from_pixel(300, 150, Luma([255])));
+        let second_result = OcrResult::from_lines(
+            300,
+            150,
+            vec![Line::from_words(vec![Word::new(
+                "second",
+                BoundingBox::new(10.0, 10.0, 100.0, 20.0),
+                0.9,
+            )])],
+        );
+        let mut result = first_result.clone();
+        result.pages.extend(second_result.pages);
+
+        // Each page keeps its own words
+        let document = Document::load_mem(&result.to_pdf([&first, &second]).unwrap()).unwrap();
+        assert_eq!(document.get_pages().len(), 2);
+        assert_eq!(
+            document.extract_text(&[1]).unwrap(),
+            "Hello wörld\n日本語\n"
+        );
+        assert_eq!(document.extract_text(&[2]).unwrap(), "second\n");
+
+        assert!(result.to_pdf([&first]).is_err());
+        assert!(first_result.to_pdf([&first, &second]).is_err());
+    }
+
+    #[test]
+    fn test_text_layer_font_is_embedded() {
+        let (image, result) = scan();
+        let document = Document::load_mem(&result.to_pdf([&image]).unwrap()).unwrap();
+
+        let descriptor = document
+            .objects
+            .values()
+            .filter_map(|object| object.as_dict().ok())
+            .find(|dict| dict.has_type(b"FontDescriptor"))
+            .unwrap();
+        let font_file = document
+            .get_object(
+                descriptor
+                    .get(b"FontFile2")
+                    .unwrap()
+                    .as_reference()
+                    .unwrap(),
+            )
+            .unwrap()
+            .as_stream()
+            .unwrap();
+        let font = font_file.decompressed_content().unwrap();
+        assert_eq!(
+            font_file.dict.get(b"Length1").unwrap().as_i64().unwrap(),
+            font.len() as i64
+        );
+
+        // A TrueType font whose checksum adjustment makes the whole file sum to the magic
+        assert_eq!(font[..4], [0, 1, 0, 0]);
+        let sum = font.chunks(4).fold(0u32, |sum, chunk| {
+            sum.wrapping_add(u32::from_be_bytes(chunk.try_into().unwrap()))
+        });
+        assert_eq!(sum, 0xB1B0_AFBA);
+    }
+
+    /// Reports the size of the image it was given and the box around its dark pixels.
+    fn ink_box(image: &DynamicImage) -> Result<OcrResult> {
+        let gray = image.to_luma8();
+        let ink: Vec<(u32, u32)> = gray
+            .enumerate_pixels()
+            .filter(|(_, _, p)| p[0] < 128)
+            .map(|(x, y, _)| (x, y))
+            .collect();
+        let left = ink.iter().map(|p| p.0).min().unwrap_or(0);
+        let top = ink.iter().map(|p| p.1).min().unwrap_or(0);
+        let right = ink.iter().map(|p| p.0 + 1).max().unwrap_or(0);
+        let bottom = ink.iter().map(|p| p.1 + 1).max().unwrap_or(0);
+        let word = Word::new(
+            format!("{}x{}", gray.width(), gray.height()),
+            BoundingBox::new(
+                left as f64,
+                top as f64,
+                (right - left) as f64,
+                (bottom - top) as f64,
+            ),
+            0.8,
+        );
+        one_line(image, vec![word])
+    }
+
+    #[tokio::test]
+    async fn test_pdf_input() {
+        // A page with a text layer, then a scan without one
+        let (first, first_result) = scan();
+        let scanned = DynamicImage::ImageLuma8(GrayImage::from_fn(600, 300, |x, y| {
+            if (100..300).contains(&x) && (50..90).contains(&y) {
+                Luma([0])
+            } else {
+                Luma([255])
+            }
+        }));
+        let mut pdf = PdfWriter::new();
+        pdf.add_page(&first, &first_result.pages[0])
+            .unwrap()
+            .add_page(&scanned, &Page::default())
+            .unwrap();
+        let file = tempfile::Builder::new().suffix(".pdf").tempfile().unwrap();
+        pdf.save(file.path()).unwrap();
+
+        let backend = FnBackend::new("ink", ink_box);
+        let engine = backend
+            .engine()
+            .with_options(OcrOptions::default().pdf_dpi(150));
+        let result = engine
+            .recognize_file(file.path().to_str().unwrap())
+            .await
+            .unwrap();
+
+        // Only the scan was recognized
+        assert_eq!(backend.calls(), 1);
+        assert_eq!(result.pages.len(), 2);
+        assert_eq!(result.provider.as_deref(), Some("ink"));
+        assert!(!result.confidence_reported);
+
+        // The text layer, with boxes at 150 dpi
+        let text_page = &result.pages[0];
+        assert_eq!(text_page.number, 1);
+        assert_eq!((text_page.width, text_page.height), (300, 150));
+        assert_eq!(text_page.text(), "Hello wörld\n日本語");
+        let hello = text_page.words().next().unwrap();
+        assert_eq!(hello.confidence, 1.0);
+        for (actual, expected) in [
+            (hello.bbox.x, 15.0),
+            (hello.bbox.y, 30.0),
+            (hello.bbox.width, 75.0),
+            (hello.bbox.height, 15.0),
+        ] {
+            assert!((actual - expected).abs() < 0.5, "{:?}", hello.bbox);
+        }
+
+        // The scan, rendered at 150 dpi
+        let scan_page = &result.pages[1];
+        assert_eq!(scan_page.number, 2);
+        assert_eq!(scan_page.text(), "300x150");
+        let ink = scan_page.words().next().unwrap();
+        assert_eq!(ink.bbox, BoundingBox::new(50.0, 25.0, 100.0, 20.0));
+    }
+
+    /// A 600x300 scan, dark where the ink rectangle of `test_pdf_input` is.
+    fn ink(x: u32, y: u32) -> bool {
+        (100..300).contains(&x) && (50..90).contains(&y)
+    }
+
+    /// A one-page PDF of 600x300 points showing `image` over the whole page, then `text`.
+    fn scan_pdf(image: Stream, text: &str, rotate: i64) -> tempfile::NamedTempFile {
+        let mut document = Document::with_version("1.7");
+        let pages_id = document.new_object_id();
+        let image_id = document.add_object(image);
+        let font_id = document.add_object(dictionary! {
+            "Type" => "Font",
+            "Subtype" => "Type1",
+            "BaseFont" => "Helvetica",
+        });
+        let content = format!("q 600 0 0 300 0 0 cm /Im0 Do Q\n{}", text);
+        let content_id = document.add_object(Stream::new(dictionary! {}, content.into_bytes()));
+        let page_id = document.add_object(dictionary! {
+            "Type" => "Page",
+            "Parent" => pages_id,
+            "MediaBox" => vec![0.into(), 0.into(), 600.into(), 300.into()],
+            "Rotate" => rotate,
+            "Contents" => content_id,
+            "Resources" => dictionary! {
+                "XObject" => dictionary! { "Im0" => image_id },
+                "Font" => dictionary! { "F1" => font_id },
+            },
+        });
+        document.objects.insert(
+            pages_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Pages",
+                "Kids" => vec![page_id.into()],
+                "Count" => 1,
+            }),
+        );
+        let catalog_id = document.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id,
+        });
+        document.trailer.set("Root", catalog_id);
+
+        let file = tempfile::Builder::new().suffix(".pdf").tempfile().unwrap();
+        document.save(file.path()).unwrap();
+        file
+    }
+
+    fn gray_scan() -> Stream {
+        let pixels = GrayImage::from_fn(600, 300, |x, y| Luma([if ink(x, y) { 0 } else { 255 }]));
+        Stream::new(
+            dictionary! {
+                "Type" => "XObject",
+                "Subtype" => "Image",
+                "Width" => 600,
+                "Height" => 300,
+                "ColorSpace" => "DeviceGray",
+                "BitsPerComponent" => 8,
+            },
+            pixels.into_raw(),
+        )
+    }
+
+    async fn recognize_pdf(file: &tempfile::NamedTempFile) -> (OcrResult, usize) {
+        let backend = FnBackend::new("ink", ink_box);
+        let engine = backend
+            .engine()
+            .with_options(OcrOptions::default().pdf_dpi(72));
+        let result = engine
+            .recognize_file(file.path().to_str().unwrap())
+            .await
+            .unwrap();
+        (result, backend.calls())
+    }
+
+    #[tokio::test]
+    async fn test_visible_text_on_a_scan_is_merged() {
+        // A stamped footer does not stand for the scan: both are kept
+        let footer = "BT /F1 20 Tf 250 10 Td (Page 1) Tj ET";
+        let (result, calls) = recognize_pdf(&scan_pdf(gray_scan(), footer, 0)).await;
+        assert_eq!(calls, 1);
+        assert_eq!(result.pages[0].text(), "600x300\nPage 1");
+        assert!(!result.confidence_reported);
+
+        // Recognized words under a text-layer word are dropped
+        let label = "BT /F1 40 Tf 150 215 Td (INK) Tj ET";
+        let file = scan_pdf(gray_scan(), &format!("{}\n{}", label, footer), 0);
+        let (result, calls) = recognize_pdf(&file).await;
+        assert_eq!(calls, 1);
+        let words: Vec<_> = result.words().map(|w| w.text.as_str()).collect();
+        assert_eq!(words, ["INK", "Page", "1"]);
+    }
+
+    #[tokio::test]
+    async fn test_fax_image_on_a_rotated_page() {
+        let mut encoder = Encoder::new(VecWriter::new());
+        for y in 0..300 {
+            let row = (0..600).map(|x| {
+                if ink(x, y) {
+                    Color::Black
+                } else {
+                    Color::White
+                }
+            });
+            encoder.encode_line(row, 600).unwrap();
+        }
+        let image = Stream::new(
+            dictionary! {
+                "Type" => "XObject",
+                "Subtype" => "Image",
+                "Width" => 600,
+                "Height" => 300,
+                "ColorSpace" => "DeviceGray",
+                "BitsPerComponent" => 1,
+                "Filter" => "CCITTFaxDecode",
+                "DecodeParms" => dictionary! { "K" => -1, "Columns" => 600, "Rows" => 300 },
+            },
+            encoder.finish().unwrap().finish(),
+        );
+
+        // Turned a quarter clockwise, as a viewer shows it
+        let (result, calls) = recognize_pdf(&scan_pdf(image, "", 90)).await;
+        assert_eq!(calls, 1);
+        let page = &result.pages[0];
+        assert_eq!((page.width, page.height), (300, 600));
+        assert_eq!(page.text(), "300x600");
+        let ink = page.words().next().unwrap();
+        assert_eq!(ink.bbox, BoundingBox::new(210.0, 100.0, 40.0, 200.0));
+    }
+
+    #[tokio::test]
+    async fn test_oversized_page_is_rejected() {
+        let file = scan_pdf(gray_scan(), "", 0);
+        let mut document = Document::load(file.path()).unwrap();
+        let page_id = document.page_iter().next().unwrap();
+        document
+            .get_dictionary_mut(page_id)
+            .unwrap()
+            .set("MediaBox", vec![0.into(), 0.into(), 1e6.into(), 1e6.into()]);
+        document.save(file.path()).unwrap();
+
+        let backend = FnBackend::new("ink", ink_box);
+        let err = backend
+            .engine()
+            .recognize_file(file.path().to_str().unwrap())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, OcrError::InvalidImage(_)), "{:?}", err);
+        assert_eq!(backend.calls(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_unsupported_images_are_skipped() {
+        let mut image = gray_scan();
+        image.dict.set("Filter", "JBIG2Decode");
+        let footer = "BT /F1 20 Tf 250 10 Td (Page 1) Tj ET";
+        let (result, calls) = recognize_pdf(&scan_pdf(image, footer, 0)).await;
+        assert_eq!(calls, 0);
+        assert_eq!(result.text(), "Page 1");
+    }
+}
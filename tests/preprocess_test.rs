@@ -0,0 +1,197 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{one_line, FnBackend};
+    use image::{DynamicImage, GrayImage, Luma};
+    use std::time::Duration;
+    use uni_ocr::{
+        Binarization, BoundingBox, OcrError, OcrOptions, OcrResult, Preprocessor, Result, Word,
+    };
+
+    /// Dark text on a light background with some noise, or the reverse when `dark_mode`.
+    fn text_like_image(dark_mode: bool) -> DynamicImage {
+        let (text, background) = if dark_mode { (230, 30) } else { (40, 200) };
+        DynamicImage::ImageLuma8(GrayImage::from_fn(120, 40, |x, y| {
+            if (10..110).contains(&x) && (15..25).contains(&y) && x % 4 != 0 {
+                Luma([text])
+            } else if (x * 7 + y * 13) % 17 == 0 {
+                Luma([background - 20])
+            } else {
+                Luma([background])
+            }
+        }))
+    }
+
+    #[test]
+    fn test_otsu_binarization() {
+        let image = text_like_image(false);
+        let binary = Preprocessor::default()
+            .binarize(Some(Binarization::Otsu))
+            .apply(&image)
+            .to_luma8();
+
+        assert!(binary.pixels().all(|p| p[0] == 0 || p[0] == 255));
+        assert_eq!(binary.get_pixel(11, 20)[0], 0);
+        assert_eq!(binary.get_pixel(5, 5)[0], 255);
+    }
+
+    #[test]
+    fn test_scanned_document_preset() {
+        let image = text_like_image(false);
+        let cleaned = Preprocessor::scanned_document().apply(&image).to_luma8();
+
+        // Padded, binarized, and the speckles are gone
+        assert_eq!(cleaned.dimensions(), (140, 60));
+        assert!(cleaned.pixels().all(|p| p[0] == 0 || p[0] == 255));
+        assert_eq!(cleaned.get_pixel(21, 30)[0], 0);
+        assert!((0..10).all(|x| cleaned.get_pixel(10 + x, 10 + x)[0] == 255));
+    }
+
+    #[test]
+    fn test_dark_mode_inversion() {
+        let preprocessor = Preprocessor::default().invert_dark(true);
+
+        let inverted = preprocessor.apply(&text_like_image(true)).to_luma8();
+        assert_eq!(inverted.get_pixel(5, 5)[0], 225);
+        assert_eq!(inverted.get_pixel(11, 20)[0], 25);
+
+        // Light images are left alone
+        let light = text_like_image(false);
+        assert_eq!(preprocessor.apply(&light), light);
+        assert!(Preprocessor::default().is_empty());
+        assert!(!preprocessor.is_empty());
+    }
+
+    #[test]
+    fn test_dark_region_inversion() {
+        // A light sidebar next to a dark editor pane, both with text
+        let image = DynamicImage::ImageLuma8(GrayImage::from_fn(400, 200, |x, y| {
+            let text = (90..110).contains(&y) && x % 40 >= 10 && x % 40 < 30;
+            match (x < 200, text) {
+                (true, true) => Luma([40]),
+                (true, false) => Luma([220]),
+                (false, true) => Luma([210]),
+                (false, false) => Luma([30]),
+            }
+        }));
+
+        let inverted = Preprocessor::default()
+            .invert_dark_regions(true)
+            .apply(&image)
+            .to_luma8();
+        // The sidebar is untouched
+        assert_eq!(inverted.get_pixel(20, 20)[0], 220);
+        assert_eq!(inverted.get_pixel(15, 100)[0], 40);
+        // The pane now has dark text on a light background
+        assert_eq!(inverted.get_pixel(300, 20)[0], 225);
+        assert_eq!(inverted.get_pixel(295, 100)[0], 45);
+    }
+
+    #[test]
+    fn test_auto_upscale() {
+        let preprocessor = Preprocessor::default().auto_upscale(true);
+
+        // 10px text is brought to about 32px
+        let small = preprocessor.apply(&text_like_image(false));
+        assert_eq!((small.width(), small.height()), (384, 128));
+
+        // Tall text is left alone
+        let tall = DynamicImage::ImageLuma8(GrayImage::from_fn(200, 100, |x, y| {
+            if (20..60).contains(&y) && x % 8 < 5 {
+                Luma([0])
+            } else {
+                Luma([255])
+            }
+        }));
+        assert_eq!(preprocessor.apply(&tall), tall);
+    }
+
+    /// Reports a word spanning the whole image it was given, minus a 1px margin.
+    fn full_image(image: &DynamicImage) -> Result<OcrResult> {
+        let (width, height) = (image.width(), image.height());
+        let word = Word::new(
+            format!("{}x{}", width, height),
+            BoundingBox::new(1.0, 1.0, width as f64 - 2.0, height as f64 - 2.0),
+            0.9,
+        );
+        one_line(image, vec![word])
+    }
+
+    #[tokio::test]
+    async fn test_boxes_map_back_to_original_image() {
+        let engine = FnBackend::new("full-image", full_image)
+            .engine()
+            .with_options(
+                OcrOptions::default().preprocess(
+                    Preprocessor::screenshot()
+                        .auto_upscale(false)
+                        .upscale(Some(2.0))
+                        .padding(20),
+                ),
+            );
+
+        let result = engine
+            .recognize_image(&text_like_image(true))
+            .await
+            .unwrap();
+
+        // The backend saw the upscaled, padded image
+        assert_eq!(result.text(), "280x120");
+        assert_eq!((result.pages[0].width, result.pages[0].height), (120, 40));
+        // The word box was clipped to the original image
+        let word = result.words().next().unwrap();
+        assert_eq!(word.bbox, BoundingBox::new(0.0, 0.0, 120.0, 40.0));
+    }
+
+    /// Four dashed text lines sloping down to the right by `degrees`.
+    fn skewed_lines(degrees: f64) -> DynamicImage {
+        let slope = degrees.to_radians().tan();
+        DynamicImage::ImageLuma8(GrayImage::from_fn(400, 300, |x, y| {
+            let on_line = [60.0, 120.0, 180.0, 240.0].iter().any(|row| {
+                let center = row + (x as f64 - 200.0) * slope;
+                (y as f64 - center).abs() < 4.0
+            });
+            if on_line && (40..360).contains(&x) && (x / 6) % 2 == 0 {
+                Luma([20])
+            } else {
+                Luma([235])
+            }
+        }))
+    }
+
+    #[tokio::test]
+    async fn test_deskew() {
+        let engine = FnBackend::new("full-image", full_image)
+            .engine()
+            .with_options(OcrOptions::default().preprocess(Preprocessor::default().deskew(true)));
+
+        let result = engine.recognize_image(&skewed_lines(5.0)).await.unwrap();
+        let page = &result.pages[0];
+        // Straightened by turning 5° counter-clockwise
+        assert!((page.rotation + 5.0).abs() < 0.5, "{}", page.rotation);
+        assert_eq!((page.width, page.height), (400, 300));
+        let word = result.words().next().unwrap();
+        assert!(word.bbox.right() <= 400.0 && word.bbox.bottom() <= 300.0);
+
+        let straight = engine.recognize_image(&skewed_lines(0.0)).await.unwrap();
+        assert_eq!(straight.pages[0].rotation, 0.0);
+    }
+    #[tokio::test]
+    async fn test_preprocessing_counts_against_the_timeout() {
+        let backend = FnBackend::new("full-image", full_image);
+        let engine = backend.engine().with_options(
+            OcrOptions::default()
+                .timeout(Duration::ZERO)
+                .preprocess(Preprocessor::default().orientation(true)),
+        );
+
+        // Orientation detection runs tesseract, which cannot finish in no time
+        let err = engine
+            .recognize_image(&text_like_image(false))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, OcrError::Timeout(_)), "{:?}", err);
+        assert_eq!(backend.calls(), 0);
+    }
+}
@@ -0,0 +1,54 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{one_line, FnBackend};
+    use image::DynamicImage;
+    use uni_ocr::{BoundingBox, OcrError, OcrResult, Rect, Result, Word};
+
+    /// Recognizes one word, the image's size, in the middle of the image.
+    fn size(image: &DynamicImage) -> Result<OcrResult> {
+        let (width, height) = (image.width(), image.height());
+        let word = Word::new(
+            format!("{}x{}", width, height),
+            BoundingBox::new(1.0, 2.0, width as f64 - 2.0, height as f64 - 4.0),
+            0.9,
+        );
+        one_line(image, vec![word])
+    }
+
+    #[tokio::test]
+    async fn test_recognize_regions() {
+        let engine = FnBackend::new("size", size).engine();
+        let image = DynamicImage::new_rgb8(200, 100);
+
+        let results = engine
+            .recognize_regions(
+                &image,
+                &[
+                    Rect::new(10, 20, 50, 30),
+                    Rect::new(150, 80, 100, 100),
+                    Rect::new(300, 0, 10, 10),
+                ],
+            )
+            .await;
+        assert_eq!(results.len(), 3);
+
+        let title = results[0].as_ref().unwrap();
+        assert_eq!(title.text(), "50x30");
+        assert_eq!((title.pages[0].width, title.pages[0].height), (200, 100));
+        let word = title.words().next().unwrap();
+        assert_eq!(word.bbox, BoundingBox::new(11.0, 22.0, 48.0, 26.0));
+        assert_eq!(title.pages[0].blocks[0].bbox, word.bbox);
+
+        // Clipped to the image
+        let corner = results[1].as_ref().unwrap();
+        assert_eq!(corner.text(), "50x20");
+        assert_eq!(
+            corner.words().next().unwrap().bbox,
+            BoundingBox::new(151.0, 82.0, 48.0, 16.0)
+        );
+
+        assert!(matches!(results[2], Err(OcrError::InvalidImage(_))));
+    }
+}
@@ -0,0 +1,119 @@
+#[cfg(test)]
+mod tests {
+    use uni_ocr::{BoundingBox, Line, OcrResult, Word};
+
+    fn word(text: &str, x: f64, confidence: f32) -> Word {
+        Word::new(text, BoundingBox::new(x, 10.0, 20.0, 10.0), confidence)
+    }
+
+    #[test]
+    fn test_result_text_and_confidence() {
+        let result = OcrResult::from_lines(
+            200,
+            100,
+            vec![
+                Line::from_words(vec![word("hello", 0.0, 0.9), word("world", 30.0, 0.7)]),
+                Line::from_words(vec![word("again", 0.0, 0.8)]),
+            ],
+        );
+
+        assert_eq!(result.text(), "hello world\nagain");
+        let confidence = result.confidence().unwrap();
+        assert!((confidence - 0.8).abs() < 1e-6, "{}", confidence);
+
+        let line = &result.pages[0].blocks[0].paragraphs[0].lines[0];
+        assert_eq!(line.bbox, BoundingBox::new(0.0, 10.0, 50.0, 10.0));
+    }
+
+    #[test]
+    fn test_confidence_is_length_weighted() {
+        let result = OcrResult::from_lines(
+            200,
+            100,
+            vec![Line::from_words(vec![
+                word("receiver_count", 0.0, 1.0),
+                word("|", 30.0, 0.1),
+            ])],
+        );
+
+        // 14 characters at 1.0 and one at 0.1
+        let expected = (14.0 * 1.0 + 0.1) / 15.0;
+        assert!((result.confidence().unwrap() - expected).abs() < 1e-6);
+        assert!(result.confidence_reported);
+    }
+
+    #[test]
+    fn test_empty_result() {
+        let result = OcrResult::from_lines(200, 100, Vec::new());
+        assert!(result.is_empty());
+        assert_eq!(result.text(), "");
+        assert_eq!(result.confidence(), None);
+    }
+
+    #[test]
+    fn test_restrict_characters() {
+        let result = OcrResult::from_lines(
+            200,
+            100,
+            vec![
+                Line::from_words(vec![word("SN-4O7l", 0.0, 0.9), word("sum:", 30.0, 0.8)]),
+                Line::from_words(vec![word("~~", 0.0, 0.5)]),
+            ],
+        );
+
+        let allowed = "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ.-";
+        let restricted = result.clone().restrict_characters(Some(allowed), None);
+        // Look-alikes are remapped, lowercase letters upper-cased, the rest dropped
+        assert_eq!(restricted.text(), "SN-4O71 SUM");
+
+        let digits = result.clone().restrict_characters(Some("0123456789"), None);
+        assert_eq!(digits.text(), "54071 5");
+
+        let denied = result.clone().restrict_characters(None, Some("~:"));
+        assert_eq!(denied.text(), "SN-4O7l sum");
+
+        assert_eq!(result.clone().restrict_characters(None, None), result);
+    }
+
+    #[test]
+    fn test_filter_confidence() {
+        let result = OcrResult::from_lines(
+            200,
+            100,
+            vec![
+                Line::from_words(vec![word("keep", 0.0, 0.9), word("drop", 30.0, 0.6)]),
+                Line::from_words(vec![word("gone", 0.0, 0.3)]),
+            ],
+        );
+
+        let filtered = result.clone().filter_confidence(0.7);
+        assert_eq!(filtered.text(), "keep");
+        let line = &filtered.pages[0].blocks[0].paragraphs[0].lines[0];
+        assert_eq!(line.bbox, BoundingBox::new(0.0, 10.0, 20.0, 10.0));
+        assert!((line.confidence - 0.9).abs() < 1e-6);
+
+        assert!(result.clone().filter_confidence(0.95).is_empty());
+        assert_eq!(result.clone().filter_confidence(0.0), result);
+    }
+
+    #[test]
+    fn test_bounding_box_normalization() {
+        let bbox = BoundingBox::new(50.0, 25.0, 100.0, 10.0);
+        let normalized = bbox.normalized(200, 100);
+        assert_eq!(normalized, BoundingBox::new(0.25, 0.25, 0.5, 0.1));
+        assert_eq!(BoundingBox::from_normalized(&normalized, 200, 100), bbox);
+        assert_eq!(bbox.normalized(0, 0), BoundingBox::default());
+    }
+
+    #[test]
+    fn test_result_serde_roundtrip() {
+        let result = OcrResult::from_lines(
+            200,
+            100,
+            vec![Line::from_words(vec![word("hello", 0.0, 0.9)])],
+        );
+        let json = serde_json::to_string(&result).unwrap();
+        let parsed: OcrResult = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, result);
+    }
+}
@@ -1,7 +1,13 @@
 #[cfg(test)]
 mod tests {
     use std::path::PathBuf;
-    use uni_ocr::{OcrEngine, OcrProvider};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+    use uni_ocr::{
+        detect_orientation, installed_languages, Language, OcrEngine, OcrError, OcrOptions,
+        OcrProvider, Preprocessor, TesseractOptions,
+    };
 
     #[tokio::test]
     async fn test_tesseract_ocr() {
@@ -15,12 +21,214 @@ mod tests {
 
         let engine = OcrEngine::new(OcrProvider::Tesseract).expect("Failed to create OCR engine");
 
-        let (text, _, confidence) = engine.recognize_image(&image).await.expect("OCR failed");
+        let result = engine.recognize_image(&image).await.expect("OCR failed");
+        let text = result.text();
 
-        assert!(confidence.is_some());
+        assert!(result.confidence().is_some());
         assert!(!text.is_empty());
         // Test that OCR produces some meaningful text
         assert!(text.len() > 10);
         println!("Recognized text: {}", text);
     }
+
+    #[tokio::test]
+    async fn test_tesseract_ocr_timeout() {
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("tests");
+        path.push("testing_OCR.png");
+        let image = image::open(&path).expect("Failed to open image");
+
+        let engine = OcrEngine::new(OcrProvider::Tesseract)
+            .expect("Failed to create OCR engine")
+            .with_options(OcrOptions::default().timeout(Duration::from_millis(1)));
+
+        let err = engine.recognize_image(&image).await.unwrap_err();
+        assert!(
+            matches!(err, OcrError::Timeout(_)),
+            "unexpected error: {:?}",
+            err
+        );
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_tesseract_ocr_does_not_block_runtime() {
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("tests");
+        path.push("testing_OCR.png");
+        let image = image::open(&path).expect("Failed to open image");
+
+        let engine = OcrEngine::new(OcrProvider::Tesseract)
+            .expect("Failed to create OCR engine")
+            .with_max_concurrency(1);
+
+        // On a single-threaded runtime this only ticks if recognition runs elsewhere
+        let ticks = Arc::new(AtomicUsize::new(0));
+        let ticker = tokio::spawn({
+            let ticks = ticks.clone();
+            async move {
+                loop {
+                    tokio::time::sleep(Duration::from_millis(5)).await;
+                    ticks.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+        });
+
+        engine.recognize_image(&image).await.expect("OCR failed");
+        ticker.abort();
+
+        assert!(ticks.load(Ordering::Relaxed) > 0);
+    }
+
+    #[tokio::test]
+    async fn test_tesseract_probe() {
+        let status = OcrProvider::Tesseract.probe().await;
+        println!("Tesseract status: {:?}", status);
+
+        assert!(status.available, "{:?}", status.detail);
+        assert!(status.version.is_some());
+        assert!(status.languages.contains(&Language::English));
+    }
+
+    #[test]
+    fn test_tesseract_backend_languages() {
+        let tesseract = OcrProvider::Tesseract.backend().unwrap();
+        assert!(tesseract.supported_languages().contains(&Language::English));
+    }
+
+    #[tokio::test]
+    async fn test_tesseract_rejects_missing_language() {
+        let installed = installed_languages().expect("Failed to list tesseract languages");
+        assert!(installed.contains(&Language::English));
+
+        let missing = *Language::all()
+            .iter()
+            .find(|lang| !installed.contains(lang))
+            .expect("every language is installed");
+
+        let image = image::DynamicImage::new_rgb8(32, 32);
+        let engine = OcrEngine::new(OcrProvider::Tesseract)
+            .expect("Failed to create OCR engine")
+            .with_options(OcrOptions::default().languages(vec![Language::English, missing]));
+
+        let err = engine.recognize_image(&image).await.unwrap_err();
+        assert!(
+            matches!(err, OcrError::LanguageNotInstalled(_)),
+            "unexpected error: {:?}",
+            err
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tesseract_custom_options() {
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("tests");
+        path.push("testing_OCR.png");
+        let image = image::open(&path).expect("Failed to open image");
+
+        let options = OcrOptions::default().tesseract(
+            TesseractOptions::default()
+                .psm(6)
+                .oem(1)
+                .dpi(300)
+                .config_variable("preserve_interword_spaces", "1"),
+        );
+        let engine = OcrEngine::new(OcrProvider::Tesseract)
+            .expect("Failed to create OCR engine")
+            .with_options(options);
+
+        let result = engine.recognize_image(&image).await.expect("OCR failed");
+        assert!(!result.text().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_tesseract_tessdata_dir() {
+        let empty_dir = tempfile::tempdir().expect("Failed to create tempdir");
+
+        let image = image::DynamicImage::new_rgb8(32, 32);
+        let engine = OcrEngine::new(OcrProvider::Tesseract)
+            .expect("Failed to create OCR engine")
+            .with_options(
+                OcrOptions::default()
+                    .tesseract(TesseractOptions::default().tessdata_dir(empty_dir.path())),
+            );
+
+        // Languages are looked up in the configured directory, not the default one
+        let err = engine.recognize_image(&image).await.unwrap_err();
+        assert!(
+            matches!(err, OcrError::LanguageNotInstalled(_)),
+            "unexpected error: {:?}",
+            err
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tesseract_relists_languages_after_install() {
+        let dir = tempfile::tempdir().expect("Failed to create tempdir");
+
+        let image = image::DynamicImage::new_rgb8(32, 32);
+        let engine = OcrEngine::new(OcrProvider::Tesseract)
+            .expect("Failed to create OCR engine")
+            .with_options(
+                OcrOptions::default()
+                    .tesseract(TesseractOptions::default().tessdata_dir(dir.path())),
+            );
+        let err = engine.recognize_image(&image).await.unwrap_err();
+        assert!(err.to_string().contains("no tesseract traineddata for eng"));
+
+        // Not a usable model, but enough for `--list-langs` to report English
+        std::fs::write(dir.path().join("eng.traineddata"), b"").unwrap();
+        let result = engine.recognize_image(&image).await;
+        assert!(
+            !matches!(&result, Err(err) if err.to_string().contains("no tesseract traineddata")),
+            "stale language listing: {:?}",
+            result
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tesseract_orientation_correction() {
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("tests");
+        path.push("testing_OCR.png");
+        // Scanned sideways
+        let image = image::open(&path).expect("Failed to open image").rotate90();
+
+        let engine = OcrEngine::new(OcrProvider::Tesseract)
+            .expect("Failed to create OCR engine")
+            .with_options(
+                OcrOptions::default().preprocess(Preprocessor::default().orientation(true)),
+            );
+
+        let result = engine.recognize_image(&image).await.expect("OCR failed");
+        let page = &result.pages[0];
+        assert_eq!(page.rotation, 270.0);
+        assert_eq!((page.width, page.height), (image.width(), image.height()));
+        assert!(!result.text().is_empty());
+        for word in result.words() {
+            assert!(word.bbox.right() <= page.width as f64);
+            assert!(word.bbox.bottom() <= page.height as f64);
+        }
+    }
+
+    #[test]
+    fn test_tesseract_orientation_options() {
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("tests");
+        path.push("testing_OCR.png");
+        let image = image::open(&path).expect("Failed to open image").rotate90();
+
+        // No osd traineddata in an empty tessdata directory
+        let empty_dir = tempfile::tempdir().expect("Failed to create tempdir");
+        let options = OcrOptions::default()
+            .tesseract(TesseractOptions::default().tessdata_dir(empty_dir.path()));
+        assert!(detect_orientation(&image, &options).is_err());
+
+        let options = OcrOptions::default().timeout(Duration::ZERO);
+        let err = detect_orientation(&image, &options).unwrap_err();
+        assert!(
+            matches!(err, OcrError::Timeout(_)),
+            "unexpected error: {:?}",
+            err
+        );
+    }
 }
@@ -0,0 +1,155 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{one_line, FnBackend};
+    use image::{DynamicImage, GrayImage, Luma};
+    use uni_ocr::{
+        BoundingBox, OcrEngine, OcrError, OcrOptions, OcrProvider, OcrResult, Result, Tiling, Word,
+    };
+
+    /// Reads every dark rectangle as a word named after its gray level.
+    fn rectangles(image: &DynamicImage) -> Result<OcrResult> {
+        let gray = &image.to_luma8();
+        let dark_rows =
+            move |x: u32| (0..gray.height()).filter(move |&y| gray.get_pixel(x, y)[0] < 128);
+
+        let mut words = Vec::new();
+        let mut x = 0;
+        while x < gray.width() {
+            let Some(top) = dark_rows(x).next() else {
+                x += 1;
+                continue;
+            };
+            let start = x;
+            while x < gray.width() && dark_rows(x).next().is_some() {
+                x += 1;
+            }
+            let bottom = dark_rows(start).next_back().unwrap() + 1;
+            words.push(Word::new(
+                format!("word{}", gray.get_pixel(start, top)[0]),
+                BoundingBox::new(
+                    start as f64,
+                    top as f64,
+                    (x - start) as f64,
+                    (bottom - top) as f64,
+                ),
+                0.9,
+            ));
+        }
+        one_line(image, words)
+    }
+
+    /// One long line of 60px words, 30px apart, word k drawn in gray level k.
+    fn word_line() -> DynamicImage {
+        DynamicImage::ImageLuma8(GrayImage::from_fn(3000, 300, |x, y| {
+            let k = x.saturating_sub(50) / 90;
+            if x >= 50 && (x - 50) % 90 < 60 && (100..120).contains(&y) && k < 33 {
+                Luma([k as u8])
+            } else {
+                Luma([255])
+            }
+        }))
+    }
+
+    #[tokio::test]
+    async fn test_tiles_are_merged_without_duplicates() {
+        let image = word_line();
+        let backend = FnBackend::new("rectangles", rectangles);
+        let engine = backend.engine().with_options(
+            OcrOptions::default().tiling(Some(Tiling::default().tile_size(1000).overlap(200))),
+        );
+
+        let result = engine.recognize_image(&image).await.unwrap();
+        assert_eq!(backend.calls(), 4);
+
+        let expected: Vec<String> = (0..33).map(|k| format!("word{}", k)).collect();
+        assert_eq!(result.text(), expected.join(" "));
+        assert_eq!((result.pages[0].width, result.pages[0].height), (3000, 300));
+        for (k, word) in result.words().enumerate() {
+            let x = 50.0 + 90.0 * k as f64;
+            assert_eq!(word.bbox, BoundingBox::new(x, 100.0, 60.0, 20.0));
+        }
+    }
+
+    /// Like [`rectangles`], but fails on the tile holding the first word.
+    fn fails_on_first_word(image: &DynamicImage) -> Result<OcrResult> {
+        if image.to_luma8().pixels().any(|pixel| pixel[0] == 0) {
+            return Err(OcrError::Backend("engine crashed".to_string()));
+        }
+        rectangles(image)
+    }
+
+    #[tokio::test]
+    async fn test_failed_tile_falls_back_for_the_whole_image() {
+        let flaky = FnBackend::new("flaky", fails_on_first_word);
+        let backend = FnBackend::new("rectangles", rectangles);
+        let engine = OcrEngine::new(OcrProvider::Auto)
+            .unwrap()
+            .with_fallback_chain(vec![flaky.provider(), backend.provider()])
+            .with_options(
+                OcrOptions::default().tiling(Some(Tiling::default().tile_size(1000).overlap(200))),
+            );
+
+        let result = engine.recognize_image(&word_line()).await.unwrap();
+        assert_eq!(flaky.calls(), 4);
+        assert_eq!(backend.calls(), 4);
+        assert_eq!(result.provider.as_deref(), Some("rectangles"));
+        assert_eq!(result.words().count(), 33);
+    }
+
+    #[tokio::test]
+    async fn test_small_images_are_not_tiled() {
+        let backend = FnBackend::new("rectangles", rectangles);
+        let engine = backend.engine();
+
+        engine
+            .recognize_image(&DynamicImage::new_luma8(1920, 1080))
+            .await
+            .unwrap();
+        assert_eq!(backend.calls(), 1);
+
+        // Tiled by default once larger than 2048px
+        engine
+            .recognize_image(&DynamicImage::new_luma8(3000, 1080))
+            .await
+            .unwrap();
+        assert_eq!(backend.calls(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_tiling_can_be_turned_off() {
+        let backend = FnBackend::new("rectangles", rectangles);
+        let engine = backend
+            .engine()
+            .with_options(OcrOptions::default().tiling(None));
+
+        let result = engine.recognize_image(&word_line()).await.unwrap();
+        assert_eq!(backend.calls(), 1);
+        assert_eq!(result.words().count(), 33);
+    }
+
+    #[tokio::test]
+    async fn test_overlap_is_clamped_to_half_a_tile() {
+        // The default 256px overlap is larger than the tile itself
+        let tiling = Tiling::default().tile_size(200);
+        assert_eq!(tiling.overlap, 100);
+        assert_eq!(tiling.overlap(150).overlap, 100);
+
+        // Fields set directly are clamped too: 9 columns and 4 rows at a 100px stride
+        let tiling = Tiling {
+            tile_size: 200,
+            overlap: 256,
+        };
+        let backend = FnBackend::new("rectangles", rectangles);
+        let engine = backend
+            .engine()
+            .with_options(OcrOptions::default().tiling(Some(tiling)));
+
+        engine
+            .recognize_image(&DynamicImage::new_luma8(1000, 500))
+            .await
+            .unwrap();
+        assert_eq!(backend.calls(), 36);
+    }
+}
@@ -15,9 +15,10 @@ mod tests {
 
         let engine = OcrEngine::new(OcrProvider::Windows).expect("Failed to create OCR engine");
 
-        let (text, _, confidence) = engine.recognize_image(&image).await.expect("OCR failed");
+        let result = engine.recognize_image(&image).await.expect("OCR failed");
+        let text = result.text();
 
-        assert!(confidence.is_some());
+        assert!(result.confidence().is_some());
         assert!(!text.is_empty());
         // Add more specific assertions based on expected behavior
         println!("Recognized text: {}", text);